@@ -0,0 +1,8 @@
+//! Helpers shared by the benchmarks.
+
+use lib::zkp::fiat_shamir::Binding;
+
+/// The server and account that benchmarks bind their proofs to.
+pub fn binding() -> Binding {
+    Binding::from(("test-server", "alice"))
+}
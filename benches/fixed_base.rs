@@ -0,0 +1,52 @@
+//! Compares signing and verifying with and without fixed-base tables for alpha
+//! and beta, in the 2048-bit groups.
+//!
+//! Run with `cargo bench --bench fixed_base`.
+
+mod common;
+
+use common::binding;
+use criterion::{criterion_group, criterion_main, Criterion};
+use lib::zkp::{
+    signer::Signer, verifier::verify_proof, Group, PrimeOrderGroup, MODP_2048_224_GROUP,
+    MODP_2048_256_GROUP,
+};
+
+fn bench_group(c: &mut Criterion, preset: &Group) {
+    // Groups decoded from protobuf don't carry tables, while the presets do.
+    let plain = Group::try_from(&preset.to_proto()).unwrap();
+    let mut benchmarks = c.benchmark_group(format!("fixed_base/{}", preset.id()));
+
+    for (name, group) in [("plain", &plain), ("tables", preset)] {
+        let signer = Signer::from(group);
+        let secret = signer.create_random_secret();
+        let signature = signer.create_signature(&secret);
+        let proof = signer.create_proof(&secret, &binding(), b"context");
+
+        benchmarks.bench_function(format!("sign/{}", name), |b| {
+            b.iter(|| signer.create_signature(&secret))
+        });
+
+        benchmarks.bench_function(format!("prove/{}", name), |b| {
+            b.iter(|| signer.create_proof(&secret, &binding(), b"context"))
+        });
+
+        benchmarks.bench_function(format!("verify/{}", name), |b| {
+            b.iter(|| verify_proof(group, &signature, &proof, &binding(), b"context"))
+        });
+    }
+
+    benchmarks.finish();
+}
+
+fn fixed_base(c: &mut Criterion) {
+    bench_group(c, &MODP_2048_224_GROUP);
+    bench_group(c, &MODP_2048_256_GROUP);
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = fixed_base
+}
+criterion_main!(benches);
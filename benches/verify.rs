@@ -0,0 +1,83 @@
+//! Compares verifying proofs one at a time against verifying them as a batch.
+//!
+//! Run with `cargo bench --bench verify`.
+
+mod common;
+
+use common::binding;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use lib::{
+    grpc::auth::{Proof, Signature},
+    zkp::{
+        ristretto::RISTRETTO255_GROUP,
+        signer::Signer,
+        verifier::{batch::BatchVerifier, verify_proof},
+        PrimeOrderGroup, MODP_1024_160_GROUP, MODP_2048_256_GROUP,
+    },
+};
+use std::sync::Arc;
+
+const BATCH_SIZES: [usize; 3] = [1, 16, 128];
+
+fn signed_proofs<G: PrimeOrderGroup>(group: &G, n: usize) -> Vec<(Signature, Proof)> {
+    let signer = Signer::from(group);
+
+    (0..n)
+        .map(|_| {
+            let secret = signer.create_random_secret();
+
+            (
+                signer.create_signature(&secret),
+                signer.create_proof(&secret, &binding(), b"context"),
+            )
+        })
+        .collect()
+}
+
+fn bench_group<G: PrimeOrderGroup + Clone>(c: &mut Criterion, group: &G) {
+    let mut benchmarks = c.benchmark_group(format!("verify/{}", group.id()));
+    let shared = Arc::new(group.clone());
+
+    for n in BATCH_SIZES {
+        let proofs = signed_proofs(group, n);
+
+        benchmarks.bench_with_input(BenchmarkId::new("each", n), &proofs, |b, proofs| {
+            b.iter(|| {
+                proofs
+                    .iter()
+                    .map(|(signature, proof)| {
+                        verify_proof(group, signature, proof, &binding(), b"context")
+                    })
+                    .collect::<Vec<_>>()
+            })
+        });
+
+        benchmarks.bench_with_input(BenchmarkId::new("batch", n), &proofs, |b, proofs| {
+            b.iter(|| {
+                let mut batch = BatchVerifier::from(shared.clone());
+                for (signature, proof) in proofs.iter() {
+                    batch
+                        .add_proof(signature, proof, &binding(), b"context")
+                        .unwrap();
+                }
+
+                batch.verify()
+            })
+        });
+    }
+
+    benchmarks.finish();
+}
+
+fn verify(c: &mut Criterion) {
+    bench_group(c, &*MODP_1024_160_GROUP);
+    bench_group(c, &*MODP_2048_256_GROUP);
+    bench_group(c, &*RISTRETTO255_GROUP);
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = verify
+}
+criterion_main!(benches);
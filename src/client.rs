@@ -2,16 +2,25 @@ use inquire::{
     max_length, min_length, validator::Validation, Password, PasswordDisplayMode, Select, Text,
 };
 use lib::{
-    grpc::auth::{AuthClient, AuthRequest, CommitRequest, SessionId, SignUpRequest, Username},
+    grpc::auth::{
+        proof_context, unix_timestamp, AuthClient, GetGroupsRequest, GetKdfParamsRequest,
+        KdfParams, NonInteractiveAuthRequest, Proof, SessionId, SignUpRequest, Signature, Username,
+        PROOF_NONCE_LEN,
+    },
     zkp::{
-        signer::Signer, Group, MODP_0005_004_GROUP, MODP_1024_160_GROUP, MODP_2048_224_GROUP,
-        MODP_2048_256_GROUP,
+        fiat_shamir::Binding,
+        nonce::NonceMode,
+        registry::{self, RegisteredGroup},
+        secret,
+        signer::Signer,
+        Error, Group, PrimeOrderGroup,
     },
 };
 use std::{collections::HashMap, str::FromStr};
 use tonic::Request;
 use uuid::Uuid;
 
+mod client_config;
 mod config;
 
 enum ClientState {
@@ -27,8 +36,43 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let address = format!("http://{}", config::SHARED.auth_server_address);
     let mut auth_client = AuthClient::connect(address).await?;
 
+    // Load the groups that the client pins by id, which are validated as they're
+    // loaded.
+    let pinned = client_config::ClientConfig::new()?
+        .pinned_group_files
+        .iter()
+        .map(|path| Group::load(path).map(|group| (group.id().to_string(), group)))
+        .collect::<Result<HashMap<String, Group>, _>>()?;
+
+    // Fetch the groups that the server accepts sign-ups against, making sure
+    // that their parameters are actually sound before using any of them. Groups
+    // identical to a pinned group or a built-in preset are already known to be,
+    // which saves primality testing the largest of them, while groups that reuse
+    // a pinned id with other parameters are left out.
+    let groups = auth_client
+        .get_groups(Request::new(GetGroupsRequest {}))
+        .await?
+        .into_inner()
+        .groups
+        .iter()
+        .filter_map(|group| match pinned.get(&group.id) {
+            Some(pinned) if pinned.matches(group) => Some(Ok(pinned.clone().into())),
+            Some(_) => {
+                println!(
+                    "Ignoring group {}, which doesn't match its pinned file",
+                    group.id
+                );
+                None
+            }
+            None => Some(match registry::preset(&group.id) {
+                Some(preset) if preset.matches(group) => Ok(preset),
+                _ => RegisteredGroup::try_from(group),
+            }),
+        })
+        .collect::<Result<Vec<RegisteredGroup>, _>>()?;
+
     // Initialize the client state.
-    let mut usernames = HashMap::<Username, &'static Group>::new();
+    let mut usernames = HashMap::<Username, &RegisteredGroup>::new();
     let mut client_state = ClientState::Home;
 
     // Begin the client loop.
@@ -41,7 +85,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 let exit = "Exit";
 
                 // Get the user's menu selection.
-                let options = if usernames.len() > 0 {
+                let options = if !usernames.is_empty() {
                     vec![register, authenticate, exit]
                 } else {
                     vec![register, exit]
@@ -73,23 +117,31 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 }
             }
             ClientState::Register => {
-                // Ask the user to choose which mod-p group they'd like to use.
-                let groups = vec![
-                    ("0005-Bit P, 004-Bit Q", &*MODP_0005_004_GROUP),
-                    ("1024-Bit P, 160-Bit Q", &*MODP_1024_160_GROUP),
-                    ("2048-Bit P, 224-Bit Q", &*MODP_2048_224_GROUP),
-                    ("2048-Bit P, 256-Bit Q", &*MODP_2048_256_GROUP),
-                ];
-
-                let group_names: Vec<&str> = groups.iter().map(|g| g.0).collect();
-                let group_name = Select::new("Select encryption group:", group_names)
+                // Ask the user to choose which of the server's groups they'd like to use.
+                let group_names: Vec<String> = groups
+                    .iter()
+                    .map(|group| match group {
+                        RegisteredGroup::ModP(group) => {
+                            let (p_bits, q_bits) = group.bits();
+                            format!("{:04}-Bit P, {:03}-Bit Q ({})", p_bits, q_bits, group.id())
+                        }
+                        RegisteredGroup::Ristretto255(group) => {
+                            format!(
+                                "Ristretto255, {:03}-Bit Q ({})",
+                                group.order().bits(),
+                                group.id()
+                            )
+                        }
+                    })
+                    .collect();
+                let group_name = Select::new("Select encryption group:", group_names.clone())
                     .with_page_size(10)
                     .prompt()?;
-                let group = groups
+                let group = group_names
                     .iter()
-                    .find(|t| t.0 == group_name)
-                    .expect("Encryption group does not exist")
-                    .1;
+                    .position(|name| *name == group_name)
+                    .map(|index| &groups[index])
+                    .expect("Encryption group does not exist");
 
                 // Ask the user to input a username, and make sure it's unique.
                 let taken: Vec<Username> = usernames.keys().cloned().collect();
@@ -105,17 +157,33 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     .with_validator(is_unique)
                     .prompt()?;
 
-                // Ask the user to input a password.
-                let password = Password::new("Password:")
-                    .with_display_toggle_enabled()
-                    .with_display_mode(PasswordDisplayMode::Masked)
-                    .with_validator(min_length!(8, "Minimum 8 characters"))
-                    .prompt()?;
+                // Ask the user to input a password, which is zeroized once it's used.
+                let password = secret::Password::from(
+                    Password::new("Password:")
+                        .with_display_toggle_enabled()
+                        .with_display_mode(PasswordDisplayMode::Masked)
+                        .with_validator(min_length!(8, "Minimum 8 characters"))
+                        .prompt()?,
+                );
+
+                // Fetch the salt and cost the server issues for this username, and
+                // derive the secret from the password with them.
+                let kdf = auth_client
+                    .get_kdf_params(Request::new(GetKdfParamsRequest {
+                        username: username.clone(),
+                    }))
+                    .await?
+                    .into_inner()
+                    .kdf
+                    .ok_or("Server did not issue KDF parameters")?;
+                let signature = Some(match group {
+                    RegisteredGroup::ModP(group) => create_signature(&**group, &password, kdf),
+                    RegisteredGroup::Ristretto255(group) => {
+                        create_signature(&**group, &password, kdf)
+                    }
+                }?);
 
                 // Send the sign up request via the auth client.
-                let signer = Signer::try_from(group)?;
-                let secret = signer.create_secret_from_password(password);
-                let signature = Some(signer.create_signature(&secret));
 
                 match auth_client
                     .sign_up(Request::new(SignUpRequest {
@@ -148,47 +216,45 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     }
                 };
 
-                // Send the commitment request via the auth client.
-                let signer = Signer::try_from(group)?;
-                let commitment = Some(signer.create_commitment());
-                let response = match auth_client
-                    .commit(Request::new(CommitRequest {
+                // Ask the user to input the password for this username.
+                let password = secret::Password::from(
+                    Password::new(&format!("Enter password for {}:", username))
+                        .with_display_toggle_enabled()
+                        .with_display_mode(PasswordDisplayMode::Masked)
+                        .with_validator(min_length!(8, "Minimum 8 characters"))
+                        .prompt()?,
+                );
+
+                // Prove knowledge of the secret non-interactively, bound to a fresh
+                // timestamp and nonce, so that logging in takes a single round trip.
+                let kdf = auth_client
+                    .get_kdf_params(Request::new(GetKdfParamsRequest {
                         username: username.clone(),
-                        commitment,
                     }))
-                    .await
-                {
-                    Ok(response) => response.into_inner(),
-                    Err(status) => {
-                        println!("Failed to submit commitment: {}", status.message());
-                        client_state = ClientState::Home;
-                        continue 'main;
+                    .await?
+                    .into_inner()
+                    .kdf
+                    .ok_or("Server did not issue KDF parameters")?;
+                let timestamp = unix_timestamp();
+                let nonce: [u8; PROOF_NONCE_LEN] = rand::random();
+                let binding = Binding::from((config::SHARED.server_id.as_str(), username.as_str()));
+                let context = proof_context(timestamp, &nonce);
+                let proof = Some(match group {
+                    RegisteredGroup::ModP(group) => {
+                        create_proof(&**group, &password, &kdf, &binding, &context)
                     }
-                };
-                let verifier_id = response.verifier_id;
-                let challenge = match response.challenge {
-                    Some(challenge) => challenge,
-                    None => {
-                        println!("Auth server failed to return a challenge");
-                        client_state = ClientState::Home;
-                        continue 'main;
+                    RegisteredGroup::Ristretto255(group) => {
+                        create_proof(&**group, &password, &kdf, &binding, &context)
                     }
-                };
-
-                // Ask the user to input the password for this username.
-                let password = Password::new(&format!("Enter password for {}:", username))
-                    .with_display_toggle_enabled()
-                    .with_display_mode(PasswordDisplayMode::Masked)
-                    .with_validator(min_length!(8, "Minimum 8 characters"))
-                    .prompt()?;
+                }?);
 
                 // Send the authentication request via the auth client.
-                let secret = signer.create_secret_from_password(password);
-                let solution = Some(signer.create_solution(&secret, challenge));
                 let response = match auth_client
-                    .authenticate(Request::new(AuthRequest {
-                        verifier_id,
-                        solution,
+                    .authenticate_non_interactive(Request::new(NonInteractiveAuthRequest {
+                        username: username.clone(),
+                        proof,
+                        timestamp,
+                        nonce: nonce.to_vec(),
                     }))
                     .await
                 {
@@ -249,3 +315,34 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// Derives the secret from the password, and creates the signature to sign up
+/// with, along with the KDF parameters so that they can be fetched again at login.
+fn create_signature<G: PrimeOrderGroup>(
+    group: &G,
+    password: &secret::Password,
+    kdf: KdfParams,
+) -> Result<Signature, Error> {
+    let signer = Signer::from(group);
+    let secret = signer.create_secret_from_password(password, &kdf)?;
+
+    Ok(Signature {
+        kdf: Some(kdf),
+        ..signer.create_signature(&secret)
+    })
+}
+
+/// Derives the secret from the password, and proves knowledge of it. The proof's
+/// nonce is hedged, so a weak RNG on this device can't leak the secret.
+fn create_proof<G: PrimeOrderGroup>(
+    group: &G,
+    password: &secret::Password,
+    kdf: &KdfParams,
+    binding: &Binding,
+    context: &[u8],
+) -> Result<Proof, Error> {
+    let signer = Signer::from((group, NonceMode::Hedged));
+    let secret = signer.create_secret_from_password(password, kdf)?;
+
+    Ok(signer.create_proof(&secret, binding, context))
+}
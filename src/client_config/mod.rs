@@ -0,0 +1,17 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct ClientConfig {
+    #[serde(default)]
+    pub pinned_group_files: Vec<String>,
+}
+
+impl ClientConfig {
+    pub fn new() -> Result<Self, config::ConfigError> {
+        let conf = config::Config::builder()
+            .add_source(config::File::with_name("config/client.toml"))
+            .build()?;
+
+        conf.try_deserialize()
+    }
+}
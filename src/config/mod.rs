@@ -9,6 +9,7 @@ lazy_static! {
 #[derive(Deserialize)]
 pub struct SharedConfig {
     pub auth_server_address: String,
+    pub server_id: String,
 }
 
 impl SharedConfig {
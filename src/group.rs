@@ -0,0 +1,103 @@
+//! Generates custom groups from verifiable seeds, and checks them again later.
+//!
+//! ```text
+//! group generate <id> <p-bits> <q-bits>   Print a new group as a [[custom_groups]] entry
+//! group verify <file>                     Check every [[custom_groups]] entry in a TOML file
+//! group convert <from> <to>               Copy a group file, converting between formats
+//! ```
+//!
+//! The output of `generate` can be appended to `config/server.toml` as is. Group
+//! files can be TOML, JSON or PEM, going by their extensions; `convert` also
+//! takes the first entry of a TOML file of `[[custom_groups]]`, to turn it into
+//! a group file of its own.
+
+use lib::zkp::{generation, group_file::GroupDefinition, Group, PrimeOrderGroup};
+use serde::Deserialize;
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage:
+    group generate <id> <p-bits> <q-bits>
+    group verify <file>
+    group convert <from> <to>";
+
+/// A TOML file of custom groups, such as the server config.
+#[derive(Deserialize)]
+struct GroupFile {
+    #[serde(default)]
+    custom_groups: Vec<GroupDefinition>,
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let result = match args.as_slice() {
+        ["generate", id, p_bits, q_bits] => generate(id, p_bits, q_bits),
+        ["verify", path] => verify(path),
+        ["convert", from, to] => convert(from, to),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn generate(id: &str, p_bits: &str, q_bits: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let (group, _) = generation::generate(id, p_bits.parse()?, q_bits.parse()?)?;
+
+    // The definition carries the seed and counter along with the parameters.
+    println!("[[custom_groups]]");
+    print!("{}", toml::to_string(&GroupDefinition::from(group))?);
+
+    Ok(())
+}
+
+fn verify(path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let file = read_custom_groups(path)?;
+
+    for custom in file.custom_groups.iter() {
+        Group::try_from(custom.clone())?;
+
+        if custom.domain_parameter_seed.is_some() {
+            println!("{}: valid, and generated from its seed", custom.id);
+        } else {
+            println!(
+                "{}: valid, but has no seed to check its generation",
+                custom.id
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn convert(from: &str, to: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let group = match Group::load(from) {
+        Ok(group) => group,
+        // Fall back to the first of a file's custom groups, as from `generate`.
+        Err(error) => match read_custom_groups(from).ok().map(|file| file.custom_groups) {
+            Some(customs) if !customs.is_empty() => Group::try_from(customs[0].clone())?,
+            _ => return Err(error.into()),
+        },
+    };
+
+    group.save(to)?;
+    println!("Wrote group {} to {}", group.id(), to);
+
+    Ok(())
+}
+
+fn read_custom_groups(path: &str) -> Result<GroupFile, config::ConfigError> {
+    config::Config::builder()
+        .add_source(config::File::new(path, config::FileFormat::Toml))
+        .build()?
+        .try_deserialize()
+}
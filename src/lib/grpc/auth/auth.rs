@@ -9,6 +9,48 @@ pub struct ProtoGroup {
     pub alpha: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes = "vec", tag = "4")]
     pub beta: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, tag = "5")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(enumeration = "GroupKind", tag = "6")]
+    pub kind: i32,
+    /// The seed that beta is hashed from, so that anyone can recompute it and be
+    /// sure that nobody knows log_alpha(beta).
+    #[prost(string, tag = "7")]
+    pub beta_seed: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetGroupsRequest {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetGroupsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub groups: ::prost::alloc::vec::Vec<ProtoGroup>,
+}
+/// Argon2id parameters for deriving a user's secret from their password.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KdfParams {
+    #[prost(bytes = "vec", tag = "1")]
+    pub salt: ::prost::alloc::vec::Vec<u8>,
+    #[prost(uint32, tag = "2")]
+    pub memory_kib: u32,
+    #[prost(uint32, tag = "3")]
+    pub iterations: u32,
+    #[prost(uint32, tag = "4")]
+    pub parallelism: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetKdfParamsRequest {
+    #[prost(string, tag = "1")]
+    pub username: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetKdfParamsResponse {
+    #[prost(message, optional, tag = "1")]
+    pub kdf: ::core::option::Option<KdfParams>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -19,6 +61,8 @@ pub struct Signature {
     pub y1: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes = "vec", tag = "3")]
     pub y2: ::prost::alloc::vec::Vec<u8>,
+    #[prost(message, optional, tag = "4")]
+    pub kdf: ::core::option::Option<KdfParams>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -47,11 +91,16 @@ pub struct CommitRequest {
     #[prost(message, optional, tag = "2")]
     pub commitment: ::core::option::Option<Commitment>,
 }
+/// The verifier's challenge, derived from the transcript and a fresh random
+/// nonce, so that the prover can check that it's bound to the server and account
+/// that they meant to authenticate to.
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Challenge {
     #[prost(bytes = "vec", tag = "1")]
     pub c: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub nonce: ::prost::alloc::vec::Vec<u8>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -81,6 +130,71 @@ pub struct AuthResponse {
     #[prost(string, tag = "1")]
     pub session_id: ::prost::alloc::string::String,
 }
+/// A Fiat-Shamir proof, whose challenge is derived by hashing the group, the
+/// server id, the username, the signature, the commitment and the request context
+/// rather than sent by the server.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Proof {
+    #[prost(message, optional, tag = "1")]
+    pub commitment: ::core::option::Option<Commitment>,
+    #[prost(message, optional, tag = "2")]
+    pub solution: ::core::option::Option<Solution>,
+}
+/// A 1-of-N (CDS) OR-proof of knowing the secret behind one of several
+/// signatures, without revealing which: one branch per signature, in order, whose
+/// challenges sum to the challenge hashed from the whole transcript.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OrProof {
+    #[prost(message, repeated, tag = "1")]
+    pub branches: ::prost::alloc::vec::Vec<OrProofBranch>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OrProofBranch {
+    #[prost(message, optional, tag = "1")]
+    pub commitment: ::core::option::Option<Commitment>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub c: ::prost::alloc::vec::Vec<u8>,
+    #[prost(message, optional, tag = "3")]
+    pub solution: ::core::option::Option<Solution>,
+}
+/// A proof that u = g^x and v = h^x for the same secret x, over arbitrary bases
+/// g and h: the challenge c, hashed from the bases, the public values, the
+/// commitment and the context, and the solution s.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DleqProof {
+    #[prost(bytes = "vec", tag = "1")]
+    pub c: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub s: ::prost::alloc::vec::Vec<u8>,
+}
+/// A VRF proof: gamma = H(input)^x, with a DLEQ proof that it shares x with the
+/// signature's y1 = alpha^x. The VRF output is a hash of gamma.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VrfProof {
+    #[prost(bytes = "vec", tag = "1")]
+    pub gamma: ::prost::alloc::vec::Vec<u8>,
+    #[prost(message, optional, tag = "2")]
+    pub proof: ::core::option::Option<DleqProof>,
+}
+/// A Fiat-Shamir login proof, bound to a timestamp within a minute of the
+/// server's clock and a 16-byte nonce that the server accepts once per account.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NonInteractiveAuthRequest {
+    #[prost(string, tag = "1")]
+    pub username: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "2")]
+    pub proof: ::core::option::Option<Proof>,
+    #[prost(uint64, tag = "3")]
+    pub timestamp: u64,
+    #[prost(bytes = "vec", tag = "4")]
+    pub nonce: ::prost::alloc::vec::Vec<u8>,
+}
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetPriceRequest {
@@ -97,6 +211,35 @@ pub struct GetPriceResponse {
     #[prost(string, tag = "2")]
     pub price: ::prost::alloc::string::String,
 }
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum GroupKind {
+    /// A multiplicative subgroup of integers mod p; every field is a big-endian integer.
+    Modp = 0,
+    /// The Ristretto255 prime-order curve group; p is unused, q is the big-endian
+    /// group order and alpha and beta (like y1, y2, r1 and r2) are compressed points.
+    Ristretto255 = 1,
+}
+impl GroupKind {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            GroupKind::Modp => "MODP",
+            GroupKind::Ristretto255 => "RISTRETTO255",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "MODP" => Some(Self::Modp),
+            "RISTRETTO255" => Some(Self::Ristretto255),
+            _ => None,
+        }
+    }
+}
 /// Generated client implementations.
 pub mod auth_client {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
@@ -182,6 +325,52 @@ pub mod auth_client {
             self.inner = self.inner.max_encoding_message_size(limit);
             self
         }
+        /// Group Discovery Routes
+        pub async fn get_groups(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetGroupsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetGroupsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/auth.Auth/GetGroups");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("auth.Auth", "GetGroups"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Key Derivation Routes
+        pub async fn get_kdf_params(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetKdfParamsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetKdfParamsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/auth.Auth/GetKdfParams");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("auth.Auth", "GetKdfParams"));
+            self.inner.unary(req, path, codec).await
+        }
         /// ZKP Authentication Routes
         pub async fn sign_up(
             &mut self,
@@ -240,6 +429,28 @@ pub mod auth_client {
             req.extensions_mut().insert(GrpcMethod::new("auth.Auth", "Authenticate"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn authenticate_non_interactive(
+            &mut self,
+            request: impl tonic::IntoRequest<super::NonInteractiveAuthRequest>,
+        ) -> std::result::Result<tonic::Response<super::AuthResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/auth.Auth/AuthenticateNonInteractive",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("auth.Auth", "AuthenticateNonInteractive"));
+            self.inner.unary(req, path, codec).await
+        }
         /// Protected Routes
         pub async fn get_price(
             &mut self,
@@ -272,6 +483,22 @@ pub mod auth_server {
     /// Generated trait containing gRPC methods that should be implemented for use with AuthServer.
     #[async_trait]
     pub trait Auth: Send + Sync + 'static {
+        /// Group Discovery Routes
+        async fn get_groups(
+            &self,
+            request: tonic::Request<super::GetGroupsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetGroupsResponse>,
+            tonic::Status,
+        >;
+        /// Key Derivation Routes
+        async fn get_kdf_params(
+            &self,
+            request: tonic::Request<super::GetKdfParamsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetKdfParamsResponse>,
+            tonic::Status,
+        >;
         /// ZKP Authentication Routes
         async fn sign_up(
             &self,
@@ -285,6 +512,10 @@ pub mod auth_server {
             &self,
             request: tonic::Request<super::AuthRequest>,
         ) -> std::result::Result<tonic::Response<super::AuthResponse>, tonic::Status>;
+        async fn authenticate_non_interactive(
+            &self,
+            request: tonic::Request<super::NonInteractiveAuthRequest>,
+        ) -> std::result::Result<tonic::Response<super::AuthResponse>, tonic::Status>;
         /// Protected Routes
         async fn get_price(
             &self,
@@ -373,6 +604,94 @@ pub mod auth_server {
         fn call(&mut self, req: http::Request<B>) -> Self::Future {
             let inner = self.inner.clone();
             match req.uri().path() {
+                "/auth.Auth/GetGroups" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetGroupsSvc<T: Auth>(pub Arc<T>);
+                    impl<T: Auth> tonic::server::UnaryService<super::GetGroupsRequest>
+                    for GetGroupsSvc<T> {
+                        type Response = super::GetGroupsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetGroupsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Auth>::get_groups(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetGroupsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/auth.Auth/GetKdfParams" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetKdfParamsSvc<T: Auth>(pub Arc<T>);
+                    impl<T: Auth> tonic::server::UnaryService<super::GetKdfParamsRequest>
+                    for GetKdfParamsSvc<T> {
+                        type Response = super::GetKdfParamsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetKdfParamsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Auth>::get_kdf_params(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetKdfParamsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/auth.Auth/SignUp" => {
                     #[allow(non_camel_case_types)]
                     struct SignUpSvc<T: Auth>(pub Arc<T>);
@@ -505,6 +824,53 @@ pub mod auth_server {
                     };
                     Box::pin(fut)
                 }
+                "/auth.Auth/AuthenticateNonInteractive" => {
+                    #[allow(non_camel_case_types)]
+                    struct AuthenticateNonInteractiveSvc<T: Auth>(pub Arc<T>);
+                    impl<
+                        T: Auth,
+                    > tonic::server::UnaryService<super::NonInteractiveAuthRequest>
+                    for AuthenticateNonInteractiveSvc<T> {
+                        type Response = super::AuthResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::NonInteractiveAuthRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Auth>::authenticate_non_interactive(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = AuthenticateNonInteractiveSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/auth.Auth/GetPrice" => {
                     #[allow(non_camel_case_types)]
                     struct GetPriceSvc<T: Auth>(pub Arc<T>);
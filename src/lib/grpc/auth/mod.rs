@@ -1,46 +1,162 @@
-use crate::zkp::verifier::Verifier;
+use crate::zkp::{
+    fiat_shamir::Binding,
+    kdf::KdfIssuer,
+    registry::{GroupRegistry, RegisteredVerifier},
+    Error,
+};
 pub use auth::{
     auth_client::AuthClient,
     auth_server::{Auth, AuthServer},
-    AuthRequest, AuthResponse, Challenge, CommitRequest, CommitResponse, Commitment,
-    GetPriceRequest, GetPriceResponse, ProtoGroup, SignUpRequest, SignUpResponse, Signature,
-    Solution,
+    AuthRequest, AuthResponse, Challenge, CommitRequest, CommitResponse, Commitment, DleqProof,
+    GetGroupsRequest, GetGroupsResponse, GetKdfParamsRequest, GetKdfParamsResponse,
+    GetPriceRequest, GetPriceResponse, GroupKind, KdfParams, NonInteractiveAuthRequest, OrProof,
+    OrProofBranch, Proof, ProtoGroup, SignUpRequest, SignUpResponse, Signature, Solution, VrfProof,
 };
 use num_bigint::BigUint;
 use parking_lot::RwLock;
 use std::{
     collections::{HashMap, HashSet},
     str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
 };
 use tonic::{Request, Response, Status};
 use tracing::{debug, error, info, instrument, Span};
 use uuid::Uuid;
 
+#[allow(clippy::module_inception)]
 mod auth;
 
 pub type Username = String;
 pub type SessionId = Uuid;
 type VerifierId = Uuid;
+type ProofNonce = [u8; PROOF_NONCE_LEN];
+
+/// How far (in seconds) a non-interactive proof's timestamp may be from the
+/// server's clock before the proof is rejected as stale.
+pub const PROOF_MAX_AGE_SECS: u64 = 60;
+
+/// The exact length (in bytes) of a non-interactive proof's nonce.
+pub const PROOF_NONCE_LEN: usize = 16;
 
 #[derive(Debug, Default)]
 pub struct AuthService {
+    server_id: String,
+    groups: GroupRegistry,
+    kdf: KdfIssuer,
     signatures: RwLock<HashMap<Username, Signature>>,
-    verifiers: RwLock<HashMap<VerifierId, Verifier>>,
+    verifiers: RwLock<HashMap<VerifierId, RegisteredVerifier>>,
+    proof_nonces: RwLock<HashMap<(Username, ProofNonce), u64>>,
     sessions: RwLock<HashSet<SessionId>>,
 }
 
 impl AuthService {
-    pub fn new() -> Self {
+    /// Creates a service known to clients as `server_id`, which every proof and
+    /// challenge is bound to.
+    pub fn new(server_id: &str, groups: GroupRegistry, kdf: KdfIssuer) -> Self {
         Self {
+            server_id: server_id.to_string(),
+            groups,
+            kdf,
             signatures: RwLock::new(HashMap::new()),
             verifiers: RwLock::new(HashMap::new()),
+            proof_nonces: RwLock::new(HashMap::new()),
             sessions: RwLock::new(HashSet::new()),
         }
     }
+
+    /// Creates and safely stores a new session id (in memory, for demo purposes).
+    fn create_session(&self) -> AuthResponse {
+        let session_id = Uuid::new_v4();
+        let session_id_string = session_id.to_string();
+
+        self.sessions.write().insert(session_id);
+
+        AuthResponse {
+            session_id: session_id_string,
+        }
+    }
+}
+
+/// Returns the current unix time, in seconds.
+pub fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Builds the context that a non-interactive login proof is bound to, besides
+/// the server and username, so that a proof made at one time and with one nonce
+/// can't be replayed at another.
+///
+/// The client picks the nonce, so there's no round trip for a server nonce;
+/// instead the server only accepts timestamps within [`PROOF_MAX_AGE_SECS`] of
+/// its clock, and remembers each account's nonces for that long.
+pub fn proof_context(timestamp: u64, nonce: &[u8]) -> Vec<u8> {
+    let mut context = Vec::new();
+
+    for part in [
+        b"zkp-chaum-pedersen/login".as_slice(),
+        &timestamp.to_be_bytes(),
+        nonce,
+    ] {
+        context.extend_from_slice(&(part.len() as u64).to_be_bytes());
+        context.extend_from_slice(part);
+    }
+
+    context
 }
 
 #[tonic::async_trait]
 impl Auth for AuthService {
+    #[instrument(
+        skip(self, _request),
+        fields(
+            request_id = %Uuid::new_v4(),
+        )
+    )]
+    async fn get_groups(
+        &self,
+        _request: Request<GetGroupsRequest>,
+    ) -> Result<Response<GetGroupsResponse>, Status> {
+        // Return every group that the server accepts sign-ups against, leaving out
+        // any below its minimum security level.
+        let groups = self
+            .groups
+            .accepted_groups()
+            .iter()
+            .map(|group| group.to_proto())
+            .collect();
+
+        Ok(Response::new(GetGroupsResponse { groups }))
+    }
+
+    #[instrument(
+        skip(self, request),
+        fields(
+            request_id = %Uuid::new_v4(),
+            username = %request.get_ref().username,
+        )
+    )]
+    async fn get_kdf_params(
+        &self,
+        request: Request<GetKdfParamsRequest>,
+    ) -> Result<Response<GetKdfParamsResponse>, Status> {
+        let request = request.into_inner();
+
+        // Registered users keep the parameters they signed up with, while anyone
+        // else gets the ones they would sign up with. Either way the salt is fixed
+        // per username, so the response doesn't reveal whether the user exists.
+        let stored = self
+            .signatures
+            .read()
+            .get(&request.username)
+            .and_then(|signature| signature.kdf.clone());
+        let kdf = stored.unwrap_or_else(|| self.kdf.issue(&request.username));
+
+        Ok(Response::new(GetKdfParamsResponse { kdf: Some(kdf) }))
+    }
+
     #[instrument(
         skip(self, request),
         fields(
@@ -78,6 +194,33 @@ impl Auth for AuthService {
         // Record p, q, alpha and beta to the current tracing span.
         span.record("group", group.tracing_string().as_str());
 
+        // Make sure the group is one the server accepts, with exactly the registered parameters.
+        let group = match self.groups.resolve(group) {
+            Ok(group) => group,
+            Err(error) => {
+                info!("Group rejected => {}", error);
+                return Err(error.into());
+            }
+        };
+
+        // Make sure the secret was derived with the salt issued to this user, and
+        // at least the server's minimum cost.
+        let kdf = signature.kdf.as_ref().ok_or_else(|| {
+            info!("KDF parameters required");
+            Status::invalid_argument("KDF parameters required")
+        })?;
+
+        if let Err(error) = self.kdf.check(&request.username, kdf) {
+            info!("KDF parameters rejected => {}", error);
+            return Err(error.into());
+        }
+
+        // Make sure y1 and y2 are canonically encoded elements of the group.
+        if let Err(error) = group.check_signature(&signature) {
+            info!("Signature rejected => {}", error);
+            return Err(error.into());
+        }
+
         // Make sure the username doesn't already exist.
         if self.signatures.read().get(&request.username).is_some() {
             info!("Username already exists");
@@ -123,12 +266,34 @@ impl Auth for AuthService {
             }
         };
 
-        // Create the verifier from the signature and commitment.
-        let verifier = match Verifier::try_from((signature, commitment)) {
+        // Look up the registered group that the signature was made against.
+        let group_id = signature
+            .group
+            .as_ref()
+            .map(|group| group.id.clone())
+            .unwrap_or_default();
+        let group = match self.groups.get(&group_id) {
+            Some(group) => group,
+            None => {
+                error!("Group {} is no longer registered", group_id);
+                return Err(Error::GroupNotRegistered(group_id).into());
+            }
+        };
+
+        // Make sure the group still meets the server's minimum security level.
+        if let Err(error) = self.groups.check_security(&group) {
+            info!("Group rejected => {}", error);
+            return Err(error.into());
+        }
+
+        // Create the verifier from the group, signature and commitment, with a
+        // challenge bound to this server and the username.
+        let binding = Binding::from((self.server_id.as_str(), request.username.as_str()));
+        let verifier = match group.create_verifier(signature, commitment, &binding) {
             Ok(verifier) => verifier,
             Err(error) => {
-                error!("Failed to create verifier => {}", error);
-                return Err(Status::internal("An internal error occurred"));
+                info!("Failed to create verifier => {}", error);
+                return Err(error.into());
             }
         };
 
@@ -187,21 +352,135 @@ impl Auth for AuthService {
             }
         };
 
-        if verifier.verify_solution(solution) {
-            // Create and safely store a session_id (in memory, for demo purposes);
-            let session_id = Uuid::new_v4();
-            let session_id_string = session_id.to_string();
-
-            self.sessions.write().insert(session_id);
-            info!("Verification passed; session_id stored in memory");
-
-            // Return the session id to the client.
-            Ok(Response::new(AuthResponse {
-                session_id: session_id_string,
-            }))
-        } else {
-            info!("Verification failed; no session_id created");
-            Err(Status::unauthenticated("Authentication failed"))
+        match verifier.verify_solution(solution) {
+            Ok(true) => {
+                // Create a session and return its id to the client.
+                let response = self.create_session();
+                info!("Verification passed; session_id stored in memory");
+
+                Ok(Response::new(response))
+            }
+            Ok(false) => {
+                info!("Verification failed; no session_id created");
+                Err(Status::unauthenticated("Authentication failed"))
+            }
+            Err(error) => {
+                info!("Failed to verify solution => {}", error);
+                Err(error.into())
+            }
+        }
+    }
+
+    #[instrument(
+        skip(self, request),
+        fields(
+            request_id = %Uuid::new_v4(),
+            username = %request.get_ref().username,
+            proof,
+        )
+    )]
+    async fn authenticate_non_interactive(
+        &self,
+        request: Request<NonInteractiveAuthRequest>,
+    ) -> Result<Response<AuthResponse>, Status> {
+        let span = Span::current();
+        let request = request.into_inner();
+
+        // Make sure that a proof was actually passed.
+        let proof = request
+            .proof
+            .ok_or_else(|| Status::invalid_argument("Proof required"))?;
+
+        // Record r1, r2 and s to the current tracing span.
+        span.record("proof", proof.tracing_string().as_str());
+
+        // Make sure the proof is fresh, i.e. its timestamp is close to the server's
+        // clock and its nonce hasn't been seen before.
+        let now = unix_timestamp();
+
+        if now.abs_diff(request.timestamp) > PROOF_MAX_AGE_SECS {
+            info!("Proof timestamp is stale");
+            return Err(Status::invalid_argument("Proof timestamp is stale"));
+        }
+
+        let nonce = match ProofNonce::try_from(request.nonce.as_slice()) {
+            Ok(nonce) => nonce,
+            Err(_) => {
+                info!("Proof nonce has the wrong length");
+                return Err(Status::invalid_argument(format!(
+                    "Proof nonce must be {} bytes",
+                    PROOF_NONCE_LEN
+                )));
+            }
+        };
+
+        // Make sure the username exists, and get the signature and group.
+        let signature = match self.signatures.read().get(&request.username) {
+            Some(signature) => signature.clone(),
+            None => {
+                info!("Username not found");
+                return Err(Status::not_found("Username not found"));
+            }
+        };
+        let group_id = signature
+            .group
+            .as_ref()
+            .map(|group| group.id.clone())
+            .unwrap_or_default();
+        let group = match self.groups.get(&group_id) {
+            Some(group) => group,
+            None => {
+                error!("Group {} is no longer registered", group_id);
+                return Err(Error::GroupNotRegistered(group_id).into());
+            }
+        };
+
+        // Make sure the group still meets the server's minimum security level.
+        if let Err(error) = self.groups.check_security(&group) {
+            info!("Group rejected => {}", error);
+            return Err(error.into());
+        }
+
+        // Recompute the challenge from this server, the username and the proof
+        // context, and check the solution against it.
+        let binding = Binding::from((self.server_id.as_str(), request.username.as_str()));
+        let context = proof_context(request.timestamp, &nonce);
+
+        match group.verify_proof(&signature, &proof, &binding, &context) {
+            Ok(true) => {
+                // Only accept each valid proof once while it could still be
+                // considered fresh. Invalid proofs never get this far, so they
+                // can't fill up the cache.
+                {
+                    let mut proof_nonces = self.proof_nonces.write();
+                    proof_nonces
+                        .retain(|_, timestamp| now.abs_diff(*timestamp) <= PROOF_MAX_AGE_SECS);
+
+                    if proof_nonces
+                        .insert((request.username.clone(), nonce), request.timestamp)
+                        .is_some()
+                    {
+                        info!("Proof nonce has already been used");
+                        return Err(Status::invalid_argument(
+                            "Proof nonce has already been used",
+                        ));
+                    }
+                }
+
+                // Create a session and return its id to the client.
+                let response = self.create_session();
+                info!("Verification passed; session_id stored in memory");
+
+                Ok(Response::new(response))
+            }
+            Ok(false) => {
+                info!("Verification failed; no session_id created");
+                Err(Status::unauthenticated("Authentication failed"))
+            }
+            Err(error) => {
+                info!("Failed to verify proof => {}", error);
+                Err(error.into())
+            }
         }
     }
 
@@ -250,14 +529,28 @@ impl Signature {
     }
 }
 
+impl KdfParams {
+    pub fn tracing_string(&self) -> String {
+        format!(
+            "{{salt: {}, memory_kib: {}, iterations: {}, parallelism: {}}}",
+            hex::encode(&self.salt),
+            self.memory_kib,
+            self.iterations,
+            self.parallelism,
+        )
+    }
+}
+
 impl ProtoGroup {
     pub fn tracing_string(&self) -> String {
         format!(
-            "{{p: {}, q: {}, alpha: {}, beta: {}}}",
+            "{{id: {}, p: {}, q: {}, alpha: {}, beta: {}, beta_seed: {:?}}}",
+            self.id,
             BigUint::from_bytes_be(self.p.as_slice()),
             BigUint::from_bytes_be(self.q.as_slice()),
             BigUint::from_bytes_be(self.alpha.as_slice()),
             BigUint::from_bytes_be(self.beta.as_slice()),
+            self.beta_seed,
         )
     }
 }
@@ -277,3 +570,19 @@ impl Solution {
         format!("{{s: {}}}", BigUint::from_bytes_be(self.s.as_slice()),)
     }
 }
+
+impl Proof {
+    pub fn tracing_string(&self) -> String {
+        format!(
+            "{{commitment: {}, solution: {}}}",
+            self.commitment
+                .as_ref()
+                .map(Commitment::tracing_string)
+                .unwrap_or_default(),
+            self.solution
+                .as_ref()
+                .map(Solution::tracing_string)
+                .unwrap_or_default(),
+        )
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{
+    grpc::auth::ProtoGroup,
+    zkp::{constant_time::Montgomery, Error},
+};
+use num_bigint::{BigUint, RandBigInt};
+use std::fmt::Debug;
+
+/// A cyclic group of prime order `q` with two generators, `alpha` and `beta`,
+/// over which Chaum-Pedersen proofs can be made.
+///
+/// Scalars (secrets, nonces, challenges and solutions) are always integers mod
+/// `q`, whatever the group, while elements are backend-specific; the
+/// multiplicative groups mod p and the Ristretto255 curve group both implement
+/// this trait, and `Signer` and `Verifier` are generic over it.
+pub trait PrimeOrderGroup: Debug + Send + Sync {
+    type Element: Clone + Debug + PartialEq + Send + Sync;
+
+    fn id(&self) -> &str;
+
+    /// Returns the (prime) order `q` of the group.
+    fn order(&self) -> &BigUint;
+
+    /// Returns `q`, prepared once for constant-time arithmetic on scalars.
+    fn montgomery_q(&self) -> &Montgomery;
+
+    /// Estimates the group's security level, in bits: roughly the base-2 log of
+    /// the work needed to compute a discrete log in it.
+    fn security_bits(&self) -> u32;
+
+    fn alpha(&self) -> &Self::Element;
+
+    fn beta(&self) -> &Self::Element;
+
+    /// Hashes arbitrary bytes to an element of the order-`q` group, other than
+    /// the identity, whose discrete log (to any base) nobody knows.
+    fn hash_to_element(&self, input: &[u8]) -> Self::Element;
+
+    /// Returns the identity element.
+    fn identity(&self) -> Self::Element;
+
+    /// Computes `base^exponent`, written multiplicatively.
+    fn exp(&self, base: &Self::Element, exponent: &BigUint) -> Self::Element;
+
+    /// Computes `base^exponent` for a secret exponent in `[0, q)`, in time that
+    /// doesn't depend on the exponent.
+    fn exp_secret(&self, base: &Self::Element, exponent: &BigUint) -> Self::Element;
+
+    /// Computes `a * b`, written multiplicatively.
+    fn mul(&self, a: &Self::Element, b: &Self::Element) -> Self::Element;
+
+    /// Computes the product of `bases[i]^exponents[i]`, for public exponents.
+    /// Backends override this with a multi-exponentiation that's cheaper than
+    /// exponentiating each base on its own.
+    fn multi_exp(&self, bases: &[Self::Element], exponents: &[BigUint]) -> Self::Element {
+        bases
+            .iter()
+            .zip(exponents)
+            .fold(self.identity(), |acc, (base, exponent)| {
+                self.mul(&acc, &self.exp(base, exponent))
+            })
+    }
+
+    /// Computes `g^a * h^b` for public exponents in a single pass (Shamir's
+    /// trick), sharing the squarings between the two terms.
+    fn double_exp(
+        &self,
+        g: &Self::Element,
+        a: &BigUint,
+        h: &Self::Element,
+        b: &BigUint,
+    ) -> Self::Element {
+        self.multi_exp(&[g.clone(), h.clone()], &[a.clone(), b.clone()])
+    }
+
+    /// Encodes an element for the wire.
+    fn encode(&self, element: &Self::Element) -> Vec<u8>;
+
+    /// Checks that an element, however it was obtained, is in the order-`q`
+    /// group, rather than elsewhere in a larger group the backend computes in.
+    fn check_element(&self, element: &Self::Element) -> Result<(), Error>;
+
+    /// Decodes an element from the wire, accepting only the canonical encoding
+    /// of an element of the order-`q` group.
+    fn decode(&self, bytes: &[u8]) -> Result<Self::Element, Error>;
+
+    /// Decodes a scalar from the wire, accepting only the canonical (minimal
+    /// big-endian) encoding of an integer in `[0, q)`.
+    fn decode_scalar(&self, bytes: &[u8]) -> Result<BigUint, Error> {
+        if bytes.is_empty() || (bytes.len() > 1 && bytes[0] == 0) {
+            return Err(Error::NonCanonicalEncoding);
+        }
+
+        let scalar = BigUint::from_bytes_be(bytes);
+
+        if scalar >= *self.order() {
+            return Err(Error::ScalarOutOfRange);
+        }
+
+        Ok(scalar)
+    }
+
+    fn to_proto(&self) -> ProtoGroup;
+
+    /// Checks whether the given protobuf group describes exactly this group.
+    fn matches(&self, group: &ProtoGroup) -> bool {
+        self.to_proto() == *group
+    }
+
+    /// Picks a uniformly random scalar in `[0, q)`.
+    fn random_scalar(&self) -> BigUint {
+        rand::thread_rng().gen_biguint_below(self.order())
+    }
+}
@@ -0,0 +1,514 @@
+use num_bigint::BigUint;
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+use subtle::{ConditionallySelectable, ConstantTimeEq};
+use zeroize::{Zeroize, Zeroizing};
+
+#[cfg(test)]
+mod test;
+
+/// The width, in bits, of each window of the exponent in `Montgomery::pow`.
+const WINDOW_BITS: usize = 4;
+
+/// The widest modulus supported, in limbs (8192 bits), so that multiplication
+/// can work in a fixed-size buffer on the stack rather than allocating.
+const MAX_LIMBS: usize = 128;
+
+/// The widest modulus supported, in bits.
+pub const MAX_MODULUS_BITS: u64 = 64 * MAX_LIMBS as u64;
+
+/// An odd modulus prepared for constant-time Montgomery arithmetic over
+/// fixed-width 64-bit limbs.
+///
+/// Every operation does the same sequence of limb operations and memory
+/// accesses whatever the (secret) operands are, and exponents are processed
+/// over a fixed number of bits, so the running time depends only on the sizes
+/// of the modulus and the exponent bound. Operands cross into and out of
+/// `BigUint` at the edges, which takes time in their number of nonzero limbs;
+/// secrets uniform in `[0, q)` almost always have the full number.
+///
+/// A context is built once per modulus (e.g. by each group, for `p` and `q`) and
+/// reused, and exponentiations work in a few buffers allocated up front, so
+/// that no step allocates.
+#[derive(Clone)]
+pub struct Montgomery {
+    modulus: BigUint,
+    /// The modulus, as little-endian limbs.
+    m: Vec<u64>,
+    /// `-m^-1 mod 2^64`.
+    m_inv: u64,
+    /// `R^2 mod m`, where `R = 2^(64 * limbs)`.
+    r2: Vec<u64>,
+}
+
+/// Precomputed powers of a fixed base, for [`Montgomery::pow_fixed`]: entry
+/// `[j][d]` is `base^(d * 16^j)`, in Montgomery form, so an exponentiation
+/// takes one multiplication per window and no squarings.
+#[derive(Clone)]
+pub struct FixedBase {
+    exponent_bits: u64,
+    table: Vec<Vec<Vec<u64>>>,
+}
+
+impl FixedBase {
+    /// Returns the width of the widest exponent that the table covers.
+    pub fn exponent_bits(&self) -> u64 {
+        self.exponent_bits
+    }
+}
+
+impl Debug for FixedBase {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_struct("FixedBase")
+            .field("exponent_bits", &self.exponent_bits)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Debug for Montgomery {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_struct("Montgomery")
+            .field("modulus", &self.modulus)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Montgomery {
+    /// Prepares an odd modulus for Montgomery arithmetic.
+    ///
+    /// Panics if the modulus is even, which no group modulus or prime order
+    /// above 2 is, or wider than 8192 bits.
+    pub fn new(modulus: &BigUint) -> Self {
+        assert!(modulus.bit(0), "Montgomery arithmetic needs an odd modulus");
+
+        let m = modulus.to_u64_digits();
+        let limbs = m.len();
+        assert!(limbs <= MAX_LIMBS, "Modulus is too wide");
+
+        // Newton's iteration doubles the number of correct low bits each time,
+        // starting from 1 (every odd number is its own inverse mod 2).
+        let mut inv = 1u64;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(m[0].wrapping_mul(inv)));
+        }
+
+        let r2 = (BigUint::from(1u32) << (128 * limbs)) % modulus;
+
+        Self {
+            modulus: modulus.clone(),
+            m_inv: inv.wrapping_neg(),
+            r2: Self::limbs(&r2, limbs).to_vec(),
+            m,
+        }
+    }
+
+    /// Computes `base^exponent mod m` with a fixed 4-bit window, for any
+    /// exponent below `2^exponent_bits`.
+    ///
+    /// All `exponent_bits` bits are processed whatever the exponent, and each
+    /// window's multiplier is read from the table with a constant-time scan.
+    pub fn pow(&self, base: &BigUint, exponent: &BigUint, exponent_bits: u64) -> BigUint {
+        assert!(exponent.bits() <= exponent_bits, "Exponent is too wide");
+
+        let windows = (exponent_bits as usize).div_ceil(WINDOW_BITS);
+        let exponent = Self::limbs(exponent, (windows * WINDOW_BITS).div_ceil(64));
+
+        let table = self.table(base);
+
+        // Every power of the base along the way depends on the secret exponent.
+        let mut acc = Zeroizing::new(self.one());
+        let mut scratch = Zeroizing::new(vec![0u64; self.m.len()]);
+        let mut multiplier = Zeroizing::new(vec![0u64; self.m.len()]);
+        for window in (0..windows).rev() {
+            for _ in 0..WINDOW_BITS {
+                self.square_assign(&mut acc, &mut scratch);
+            }
+
+            let bit = window * WINDOW_BITS;
+            let index = (exponent[bit / 64] >> (bit % 64)) & ((1 << WINDOW_BITS) - 1);
+            Self::select(&table, index, &mut multiplier);
+            self.mul_assign(&mut acc, &multiplier, &mut scratch);
+        }
+
+        Self::to_biguint(&self.decode_montgomery(&acc))
+    }
+
+    /// Precomputes the powers of `base` needed by [`Self::pow_fixed`], for any
+    /// exponent below `2^exponent_bits`.
+    pub fn fixed_base(&self, base: &BigUint, exponent_bits: u64) -> FixedBase {
+        let windows = (exponent_bits as usize).div_ceil(WINDOW_BITS);
+        let mut window_base =
+            self.encode_montgomery(&Self::limbs(&(base % &self.modulus), self.m.len()));
+
+        let table = (0..windows)
+            .map(|_| {
+                let mut powers = vec![self.one(), window_base.clone()];
+                for d in 2..1 << WINDOW_BITS {
+                    powers.push(self.mul(&powers[d - 1], &window_base));
+                }
+
+                // The next window's base is this one's raised to 2^WINDOW_BITS.
+                window_base = self.mul(&powers[(1 << WINDOW_BITS) - 1], &window_base);
+
+                powers
+            })
+            .collect();
+
+        FixedBase {
+            exponent_bits,
+            table,
+        }
+    }
+
+    /// Computes `base^exponent mod m` from the base's precomputed table, for any
+    /// exponent below `2^fixed.exponent_bits()`.
+    ///
+    /// Every window is processed, and each multiplier read with a constant-time
+    /// scan of its row, as in [`Self::pow`].
+    pub fn pow_fixed(&self, fixed: &FixedBase, exponent: &BigUint) -> BigUint {
+        assert!(
+            exponent.bits() <= fixed.exponent_bits,
+            "Exponent is too wide"
+        );
+
+        let exponent = Self::limbs(exponent, (fixed.table.len() * WINDOW_BITS).div_ceil(64));
+
+        // The rows selected depend on the secret exponent, so wipe them as in
+        // `pow`.
+        let mut acc = Zeroizing::new(self.one());
+        let mut scratch = Zeroizing::new(vec![0u64; self.m.len()]);
+        let mut multiplier = Zeroizing::new(vec![0u64; self.m.len()]);
+        for (window, powers) in fixed.table.iter().enumerate() {
+            let bit = window * WINDOW_BITS;
+            let index = (exponent[bit / 64] >> (bit % 64)) & ((1 << WINDOW_BITS) - 1);
+            Self::select(powers, index, &mut multiplier);
+            self.mul_assign(&mut acc, &multiplier, &mut scratch);
+        }
+
+        Self::to_biguint(&self.decode_montgomery(&acc))
+    }
+
+    /// Computes `base^exponent mod m` from the base's precomputed table, skipping
+    /// zero windows and indexing rows directly. Like
+    /// [`Self::multi_pow_vartime`], this must only be used with public exponents.
+    pub fn pow_fixed_vartime(&self, fixed: &FixedBase, exponent: &BigUint) -> BigUint {
+        assert!(
+            exponent.bits() <= fixed.exponent_bits,
+            "Exponent is too wide"
+        );
+
+        let exponent = Self::limbs(exponent, (fixed.table.len() * WINDOW_BITS).div_ceil(64));
+
+        let mut acc = self.one();
+        let mut scratch = vec![0u64; self.m.len()];
+        for (window, powers) in fixed.table.iter().enumerate() {
+            let bit = window * WINDOW_BITS;
+            let index = (exponent[bit / 64] >> (bit % 64)) & ((1 << WINDOW_BITS) - 1);
+            if index != 0 {
+                self.mul_assign(&mut acc, &powers[index as usize], &mut scratch);
+            }
+        }
+
+        Self::to_biguint(&self.decode_montgomery(&acc))
+    }
+
+    /// Computes the product of `bases[i]^exponents[i] mod m` with Straus'
+    /// interleaved 4-bit windows, so that all the terms share one run of
+    /// squarings.
+    ///
+    /// Unlike everything else here, this skips zero windows and indexes the
+    /// tables directly, so it takes time that depends on the exponents. It must
+    /// only be used with public exponents, e.g. to verify proofs.
+    pub fn multi_pow_vartime(&self, bases: &[BigUint], exponents: &[BigUint]) -> BigUint {
+        assert_eq!(bases.len(), exponents.len(), "Need one exponent per base");
+
+        let bits = exponents.iter().map(BigUint::bits).max().unwrap_or(0) as usize;
+        let windows = bits.div_ceil(WINDOW_BITS);
+        let tables: Vec<_> = bases.iter().map(|base| self.table(base)).collect();
+        let exponents: Vec<_> = exponents
+            .iter()
+            .map(|exponent| Self::limbs(exponent, (windows * WINDOW_BITS).div_ceil(64)))
+            .collect();
+
+        let mut acc = self.one();
+        let mut scratch = vec![0u64; self.m.len()];
+        for window in (0..windows).rev() {
+            for _ in 0..WINDOW_BITS {
+                self.square_assign(&mut acc, &mut scratch);
+            }
+
+            let bit = window * WINDOW_BITS;
+            for (table, exponent) in tables.iter().zip(exponents.iter()) {
+                let index = (exponent[bit / 64] >> (bit % 64)) & ((1 << WINDOW_BITS) - 1);
+                if index != 0 {
+                    self.mul_assign(&mut acc, &table[index as usize], &mut scratch);
+                }
+            }
+        }
+
+        Self::to_biguint(&self.decode_montgomery(&acc))
+    }
+
+    /// Computes `a * b mod m`, for `a` and `b` in `[0, m)`.
+    pub fn mul_mod(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        let n = self.m.len();
+
+        Self::to_biguint(&self.mul_limbs(&Self::limbs(a, n), &Self::limbs(b, n)))
+    }
+
+    /// Computes `a - b mod m`, for `a` and `b` in `[0, m)`.
+    pub fn sub_mod(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        let n = self.m.len();
+
+        Self::to_biguint(&self.sub_limbs(&Self::limbs(a, n), &Self::limbs(b, n)))
+    }
+
+    /// Computes `a - b * c mod m`, for `a`, `b` and `c` in `[0, m)`, without
+    /// leaving fixed-width limbs in between.
+    pub fn sub_mul_mod(&self, a: &BigUint, b: &BigUint, c: &BigUint) -> BigUint {
+        let n = self.m.len();
+        let bc = Zeroizing::new(self.mul_limbs(&Self::limbs(b, n), &Self::limbs(c, n)));
+
+        Self::to_biguint(&self.sub_limbs(&Self::limbs(a, n), &bc))
+    }
+
+    /// Reduces a big-endian integer of any width mod m, in time that depends
+    /// only on its length.
+    ///
+    /// The integer is read in chunks of as many limbs as the modulus, most
+    /// significant first, and folded in as `acc = acc * R + chunk mod m`: a
+    /// Montgomery multiplication by `R^2` gives `acc * R`, and one by `R^2` and
+    /// then 1 gives `chunk mod m`.
+    pub fn reduce(&self, bytes: &[u8]) -> BigUint {
+        let n = self.m.len();
+        let chunk_len = 8 * n;
+        let (head, tail) = bytes.split_at(bytes.len() % chunk_len);
+
+        let mut acc = Zeroizing::new(vec![0u64; n]);
+        for chunk in [head]
+            .into_iter()
+            .filter(|head| !head.is_empty())
+            .chain(tail.chunks(chunk_len))
+        {
+            let chunk = Self::limbs_from_be(chunk, n);
+            let shifted = Zeroizing::new(self.mul(&acc, &self.r2));
+            let encoded = Zeroizing::new(self.encode_montgomery(&chunk));
+            let reduced = Zeroizing::new(self.decode_montgomery(&encoded));
+
+            acc = Zeroizing::new(self.add_limbs(&shifted, &reduced));
+        }
+
+        Self::to_biguint(&acc)
+    }
+
+    fn mul_limbs(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        // a * b * R^-1 * R^2 * R^-1 = a * b.
+        self.mul(&self.mul(a, b), &self.r2)
+    }
+
+    fn add_limbs(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let mut carry = 0u64;
+        let sum: Vec<u64> = a
+            .iter()
+            .zip(b.iter())
+            .map(|(a, b)| {
+                let limb;
+                (limb, carry) = adc(*a, *b, carry);
+                limb
+            })
+            .collect();
+
+        // The sum is below 2m; subtract m unless that would wrap.
+        let (mut difference, borrow) = sub(&sum, &self.m);
+        let wrapped = (borrow ^ carry).ct_eq(&1);
+        for (limb, unreduced) in difference.iter_mut().zip(sum.iter()) {
+            limb.conditional_assign(unreduced, wrapped);
+        }
+
+        difference
+    }
+
+    fn sub_limbs(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let (mut difference, borrow) = sub(a, b);
+
+        // Add m back if the subtraction wrapped.
+        let mask = 0u64.wrapping_sub(borrow);
+        let mut carry = 0u64;
+        for (limb, m) in difference.iter_mut().zip(self.m.iter()) {
+            (*limb, carry) = adc(*limb, m & mask, carry);
+        }
+
+        difference
+    }
+
+    fn mul(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let mut product = vec![0u64; self.m.len()];
+        self.mul_into(a, b, &mut product);
+
+        product
+    }
+
+    /// Replaces `acc` with `acc * b * R^-1 mod m`, swapping it with `scratch`
+    /// rather than allocating.
+    fn mul_assign(&self, acc: &mut Vec<u64>, b: &[u64], scratch: &mut Vec<u64>) {
+        self.mul_into(acc, b, scratch);
+        std::mem::swap(acc, scratch);
+    }
+
+    /// Replaces `acc` with `acc^2 * R^-1 mod m`, as in [`Self::mul_assign`].
+    fn square_assign(&self, acc: &mut Vec<u64>, scratch: &mut Vec<u64>) {
+        self.mul_into(acc, acc, scratch);
+        std::mem::swap(acc, scratch);
+    }
+
+    /// Computes `a * b * R^-1 mod m` into `out` (coarsely integrated operand
+    /// scanning), with the intermediate sum on the stack.
+    fn mul_into(&self, a: &[u64], b: &[u64], out: &mut [u64]) {
+        let n = self.m.len();
+        let mut t = [0u64; MAX_LIMBS + 2];
+
+        for &b_i in b {
+            let mut carry = 0u64;
+            for j in 0..n {
+                (t[j], carry) = mac(t[j], a[j], b_i, carry);
+            }
+            (t[n], carry) = adc(t[n], carry, 0);
+            t[n + 1] = carry;
+
+            let u = t[0].wrapping_mul(self.m_inv);
+            let (_, mut carry) = mac(t[0], u, self.m[0], 0);
+            for j in 1..n {
+                (t[j - 1], carry) = mac(t[j], u, self.m[j], carry);
+            }
+            (t[n - 1], carry) = adc(t[n], carry, 0);
+            t[n] = t[n + 1] + carry;
+        }
+
+        // The result is below 2m; subtract m unless that would wrap.
+        let mut borrow = 0u64;
+        for ((limb, t), m) in out.iter_mut().zip(t.iter()).zip(self.m.iter()) {
+            (*limb, borrow) = sbb(*t, *m, borrow);
+        }
+
+        let wrapped = (borrow ^ t[n]).ct_eq(&1);
+        for (limb, unreduced) in out.iter_mut().zip(t.iter()) {
+            limb.conditional_assign(unreduced, wrapped);
+        }
+
+        // The intermediate sum may be a product of secrets.
+        t.zeroize();
+    }
+
+    /// Returns `base^i` for every window value `i`, in Montgomery form.
+    fn table(&self, base: &BigUint) -> Vec<Vec<u64>> {
+        let n = self.m.len();
+        let base = self.encode_montgomery(&Self::limbs(&(base % &self.modulus), n));
+
+        let mut table = vec![self.one(), base.clone()];
+        for i in 2..1 << WINDOW_BITS {
+            table.push(self.mul(&table[i - 1], &base));
+        }
+
+        table
+    }
+
+    /// Returns 1, in Montgomery form.
+    fn one(&self) -> Vec<u64> {
+        self.encode_montgomery(&Self::limbs(&BigUint::from(1u32), self.m.len()))
+    }
+
+    fn encode_montgomery(&self, a: &[u64]) -> Vec<u64> {
+        self.mul(a, &self.r2)
+    }
+
+    fn decode_montgomery(&self, a: &[u64]) -> Vec<u64> {
+        let mut one = vec![0u64; self.m.len()];
+        one[0] = 1;
+
+        self.mul(a, &one)
+    }
+
+    /// Reads `table[index]` into `selected`, touching every entry.
+    fn select(table: &[Vec<u64>], index: u64, selected: &mut [u64]) {
+        selected.fill(0);
+
+        for (i, entry) in table.iter().enumerate() {
+            let hit = (i as u64).ct_eq(&index);
+            for (limb, value) in selected.iter_mut().zip(entry.iter()) {
+                limb.conditional_assign(value, hit);
+            }
+        }
+    }
+
+    /// Splits `value` into exactly `n` little-endian limbs, which are zeroized on
+    /// drop, as the value may well be secret.
+    fn limbs(value: &BigUint, n: usize) -> Zeroizing<Vec<u64>> {
+        assert!(value.bits() <= 64 * n as u64, "Value is too wide");
+
+        let mut limbs = Zeroizing::new(vec![0u64; n]);
+        for (limb, digit) in limbs.iter_mut().zip(value.iter_u64_digits()) {
+            *limb = digit;
+        }
+
+        limbs
+    }
+
+    /// Reads at most `n` limbs' worth of big-endian bytes into exactly `n`
+    /// little-endian limbs, which are zeroized on drop.
+    fn limbs_from_be(bytes: &[u8], n: usize) -> Zeroizing<Vec<u64>> {
+        assert!(bytes.len() <= 8 * n, "Value is too wide");
+
+        let mut limbs = Zeroizing::new(vec![0u64; n]);
+        for (i, byte) in bytes.iter().rev().enumerate() {
+            limbs[i / 8] |= (*byte as u64) << (8 * (i % 8));
+        }
+
+        limbs
+    }
+
+    fn to_biguint(limbs: &[u64]) -> BigUint {
+        BigUint::from_slice(
+            &limbs
+                .iter()
+                .flat_map(|limb| [*limb as u32, (limb >> 32) as u32])
+                .collect::<Vec<u32>>(),
+        )
+    }
+}
+
+/// Computes `a + b + carry`, returning the low limb and the carry.
+fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let sum = a as u128 + b as u128 + carry as u128;
+
+    (sum as u64, (sum >> 64) as u64)
+}
+
+/// Computes `a + b * c + carry`, returning the low limb and the carry.
+fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    let sum = a as u128 + (b as u128) * (c as u128) + carry as u128;
+
+    (sum as u64, (sum >> 64) as u64)
+}
+
+/// Computes `a - b - borrow`, returning the low limb and the borrow (0 or 1).
+fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let diff = (a as u128).wrapping_sub(b as u128 + borrow as u128);
+
+    (diff as u64, (diff >> 127) as u64)
+}
+
+/// Computes `a - b` over equal-width limbs, returning the difference and the
+/// final borrow (0 or 1).
+fn sub(a: &[u64], b: &[u64]) -> (Vec<u64>, u64) {
+    let mut borrow = 0u64;
+    let difference = a
+        .iter()
+        .zip(b.iter())
+        .map(|(a, b)| {
+            let diff;
+            (diff, borrow) = sbb(*a, *b, borrow);
+            diff
+        })
+        .collect();
+
+    (difference, borrow)
+}
@@ -0,0 +1,266 @@
+use crate::zkp::{
+    constant_time::Montgomery, PrimeOrderGroup, MODP_0005_004_GROUP, MODP_1024_160_GROUP,
+    MODP_2048_256_GROUP,
+};
+use num_bigint::{BigUint, RandBigInt};
+use std::{hint::black_box, time::Instant};
+
+/// dudect's threshold on Welch's t-statistic, above which timings are taken to
+/// depend on the input class.
+const LEAKAGE_THRESHOLD: f64 = 4.5;
+
+/// The number of timing samples taken per test.
+const TIMING_SAMPLES: usize = 10_000;
+
+fn random_below(bound: &BigUint) -> BigUint {
+    rand::thread_rng().gen_biguint_below(bound)
+}
+
+#[test]
+fn pow_matches_modpow() {
+    for group in [
+        &*MODP_0005_004_GROUP,
+        &*MODP_1024_160_GROUP,
+        &*MODP_2048_256_GROUP,
+    ] {
+        let (p, q) = (&group.p, group.order());
+        let montgomery = Montgomery::new(p);
+
+        for exponent in [
+            BigUint::from(0u32),
+            BigUint::from(1u32),
+            q - 1u32,
+            random_below(q),
+        ] {
+            let base = random_below(p);
+
+            assert_eq!(
+                montgomery.pow(&base, &exponent, q.bits()),
+                base.modpow(&exponent, p)
+            );
+        }
+    }
+}
+
+#[test]
+fn mul_mod_and_sub_mod_match_biguint() {
+    for group in [&*MODP_0005_004_GROUP, &*MODP_2048_256_GROUP] {
+        let q = group.order();
+        let montgomery = Montgomery::new(q);
+
+        for (a, b) in [
+            (BigUint::from(0u32), q - 1u32),
+            (q - 1u32, q - 1u32),
+            (random_below(q), random_below(q)),
+        ] {
+            assert_eq!(montgomery.mul_mod(&a, &b), (&a * &b) % q);
+            assert_eq!(montgomery.sub_mod(&a, &b), (&a + q - &b) % q);
+            assert_eq!(montgomery.sub_mod(&b, &a), (&b + q - &a) % q);
+            assert_eq!(
+                montgomery.sub_mul_mod(&a, &a, &b),
+                (&a + q - (&a * &b) % q) % q
+            );
+        }
+    }
+}
+
+#[test]
+fn reduce_matches_biguint() {
+    for group in [
+        &*MODP_0005_004_GROUP,
+        &*MODP_1024_160_GROUP,
+        &*MODP_2048_256_GROUP,
+    ] {
+        let q = group.order();
+        let montgomery = Montgomery::new(q);
+        let q_len = q.to_bytes_be().len();
+
+        // Narrower than q, as wide as the KDF's output, and several chunks wide.
+        for len in [0, 1, q_len, q_len + 32, 8 * q_len + 3] {
+            let bytes: Vec<u8> = (0..len).map(|_| rand::random()).collect();
+
+            assert_eq!(
+                montgomery.reduce(&bytes),
+                BigUint::from_bytes_be(&bytes) % q
+            );
+        }
+
+        let all_ones = vec![0xff; q_len + 32];
+        assert_eq!(
+            montgomery.reduce(&all_ones),
+            BigUint::from_bytes_be(&all_ones) % q
+        );
+    }
+}
+
+#[test]
+fn pow_fixed_matches_modpow() {
+    for group in [&*MODP_0005_004_GROUP, &*MODP_2048_256_GROUP] {
+        let (p, q) = (&group.p, group.order());
+        let montgomery = Montgomery::new(p);
+        let base = random_below(p);
+        let fixed = montgomery.fixed_base(&base, q.bits());
+
+        for exponent in [
+            BigUint::from(0u32),
+            BigUint::from(1u32),
+            q - 1u32,
+            random_below(q),
+        ] {
+            let expected = base.modpow(&exponent, p);
+
+            assert_eq!(montgomery.pow_fixed(&fixed, &exponent), expected);
+            assert_eq!(montgomery.pow_fixed_vartime(&fixed, &exponent), expected);
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "too wide")]
+fn pow_fixed_rejects_wide_exponents() {
+    let group = &*MODP_1024_160_GROUP;
+    let montgomery = Montgomery::new(&group.p);
+    let fixed = montgomery.fixed_base(&group.alpha, group.order().bits());
+
+    montgomery.pow_fixed(&fixed, &group.p);
+}
+
+#[test]
+fn multi_pow_matches_product_of_modpows() {
+    for group in [&*MODP_0005_004_GROUP, &*MODP_1024_160_GROUP] {
+        let (p, q) = (&group.p, group.order());
+        let montgomery = Montgomery::new(p);
+
+        let bases: Vec<_> = (0..5).map(|_| random_below(p)).collect();
+        let exponents = vec![
+            BigUint::from(0u32),
+            BigUint::from(1u32),
+            q - 1u32,
+            random_below(q),
+            random_below(q),
+        ];
+        let product = bases
+            .iter()
+            .zip(exponents.iter())
+            .fold(BigUint::from(1u32), |acc, (base, exponent)| {
+                acc * base.modpow(exponent, p) % p
+            });
+
+        assert_eq!(montgomery.multi_pow_vartime(&bases, &exponents), product);
+        assert_eq!(montgomery.multi_pow_vartime(&[], &[]), BigUint::from(1u32));
+    }
+}
+
+#[test]
+#[should_panic(expected = "odd modulus")]
+fn even_modulus_is_rejected() {
+    Montgomery::new(&BigUint::from(22u32));
+}
+
+#[test]
+#[should_panic(expected = "too wide")]
+fn modulus_over_8192_bits_is_rejected() {
+    Montgomery::new(&((BigUint::from(1u32) << 8192) + 1u32));
+}
+
+/// Times `operation` on a fixed input and on random inputs, interleaved at
+/// random, and returns Welch's t-statistic between the two classes, as in
+/// dudect (https://eprint.iacr.org/2016/1123). The slowest tenth of each class
+/// is cropped to cut down on noise from the rest of the system.
+fn timing_t_statistic(
+    fixed: &BigUint,
+    random: impl Fn() -> BigUint,
+    operation: impl Fn(&BigUint) -> BigUint,
+) -> f64 {
+    let mut timings = [Vec::new(), Vec::new()];
+
+    for _ in 0..TIMING_SAMPLES {
+        let class = rand::random::<bool>() as usize;
+        let input = if class == 0 { fixed.clone() } else { random() };
+
+        let start = Instant::now();
+        black_box(operation(black_box(&input)));
+        timings[class].push(start.elapsed().as_nanos() as f64);
+    }
+
+    let [fixed, random] = timings.map(|mut class| {
+        class.sort_by(f64::total_cmp);
+        class.truncate(class.len() * 9 / 10);
+
+        let n = class.len() as f64;
+        let mean = class.iter().sum::<f64>() / n;
+        let variance = class.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / (n - 1.0);
+
+        (mean, variance, n)
+    });
+
+    (fixed.0 - random.0) / (fixed.1 / fixed.2 + random.1 / random.2).sqrt()
+}
+
+#[test]
+#[ignore = "timing test; run alone with --ignored"]
+fn pow_time_does_not_depend_on_exponent() {
+    let group = &*MODP_1024_160_GROUP;
+    let (p, q) = (&group.p, group.order());
+    let montgomery = Montgomery::new(p);
+
+    // An exponent of 1 is about as unlike a random one as exponents get.
+    let t = timing_t_statistic(
+        &BigUint::from(1u32),
+        || random_below(q),
+        |exponent| montgomery.pow(&group.alpha, exponent, q.bits()),
+    );
+
+    assert!(t.abs() < LEAKAGE_THRESHOLD, "t = {}", t);
+}
+
+#[test]
+#[ignore = "timing test; run alone with --ignored"]
+fn pow_fixed_time_does_not_depend_on_exponent() {
+    let group = &*MODP_1024_160_GROUP;
+    let (p, q) = (&group.p, group.order());
+    let montgomery = Montgomery::new(p);
+    let fixed = montgomery.fixed_base(&group.alpha, q.bits());
+
+    let t = timing_t_statistic(
+        &BigUint::from(1u32),
+        || random_below(q),
+        |exponent| montgomery.pow_fixed(&fixed, exponent),
+    );
+
+    assert!(t.abs() < LEAKAGE_THRESHOLD, "t = {}", t);
+}
+
+#[test]
+#[ignore = "timing test; run alone with --ignored"]
+fn solve_time_does_not_depend_on_secret() {
+    let q = MODP_2048_256_GROUP.order();
+    let montgomery = Montgomery::new(q);
+    let (k, c) = (random_below(q), random_below(q));
+
+    // Converting from BigUint takes time in the number of limbs, so compare
+    // against a fixed secret of full width, as random ones almost always are.
+    let t = timing_t_statistic(
+        &(q - 1u32),
+        || random_below(q),
+        |secret| montgomery.sub_mul_mod(&k, &c, secret),
+    );
+
+    assert!(t.abs() < LEAKAGE_THRESHOLD, "t = {}", t);
+}
+
+#[test]
+#[ignore = "timing test; run alone with --ignored"]
+fn harness_detects_variable_time_modpow() {
+    let group = &*MODP_1024_160_GROUP;
+    let (p, q) = (&group.p, group.order());
+
+    // The same comparison against BigUint::modpow should show a clear leak.
+    let t = timing_t_statistic(
+        &BigUint::from(1u32),
+        || random_below(q),
+        |exponent| group.alpha.modpow(exponent, p),
+    );
+
+    assert!(t.abs() > LEAKAGE_THRESHOLD, "t = {}", t);
+}
@@ -0,0 +1,114 @@
+//! Proofs of equality of discrete logs (DLEQ) for arbitrary bases: that
+//! `u = g^x` and `v = h^x` for the same secret `x`, without revealing it. This
+//! is Chaum-Pedersen with the bases left open, as needed for verifiable
+//! decryption, VRF outputs and OPRF evaluations over the same groups.
+//!
+//! Proofs are compact: just the challenge `c` and the solution `s`, from which
+//! the verifier recomputes the commitment `r1 = g^s * u^c`, `r2 = h^s * v^c` and
+//! checks that it hashes back to `c`.
+
+use crate::{
+    grpc::auth::DleqProof,
+    zkp::{secret::Secret, signer::ProverState, transcript::Transcript, Error, PrimeOrderGroup},
+};
+
+#[cfg(test)]
+mod test;
+
+/// The protocol and version that DLEQ transcripts are domain-separated by.
+pub const DLEQ_PROTOCOL_VERSION: &str = "zkp-dleq/v1";
+
+/// Checks that neither base is the identity, over which any `x` would do.
+fn check_bases<G: PrimeOrderGroup>(
+    group: &G,
+    (g, h): (&G::Element, &G::Element),
+) -> Result<(), Error> {
+    let identity = group.identity();
+
+    if *g == identity || *h == identity {
+        return Err(Error::IdentityBase);
+    }
+
+    Ok(())
+}
+
+/// Checks that the bases and public values are all in the order-`q` group.
+/// Otherwise, say with `v = -(h^x)` mod p, a prover could retry until `c` is
+/// even and have a false statement verify.
+fn check_elements<G: PrimeOrderGroup>(group: &G, elements: [&G::Element; 4]) -> Result<(), Error> {
+    elements
+        .into_iter()
+        .try_for_each(|element| group.check_element(element))
+}
+
+/// Starts the transcript of a DLEQ proof over everything but its commitment.
+fn start_transcript<G: PrimeOrderGroup>(
+    group: &G,
+    (g, h): (&G::Element, &G::Element),
+    (u, v): (&G::Element, &G::Element),
+    context: &[u8],
+) -> Transcript {
+    let mut transcript = Transcript::new(DLEQ_PROTOCOL_VERSION);
+    transcript.append_group(group);
+    transcript.append_element(group, b"g", g);
+    transcript.append_element(group, b"h", h);
+    transcript.append_element(group, b"u", u);
+    transcript.append_element(group, b"v", v);
+    transcript.append_message(b"context", context);
+
+    transcript
+}
+
+/// Creates a DLEQ proof for `u = g^x` and `v = h^x`. `commit` draws the nonce
+/// over the bases, given a context that covers the whole statement, so that a
+/// hedged nonce is never reused across statements.
+pub(super) fn prove<'a, G: PrimeOrderGroup>(
+    group: &'a G,
+    secret: &Secret,
+    (g, h): (&G::Element, &G::Element),
+    context: &[u8],
+    commit: impl FnOnce(&[u8]) -> (G::Element, G::Element, ProverState<'a, G>),
+) -> Result<DleqProof, Error> {
+    check_bases(group, (g, h))?;
+
+    let u = group.exp_secret(g, secret.expose());
+    let v = group.exp_secret(h, secret.expose());
+    check_elements(group, [g, h, &u, &v])?;
+
+    let mut transcript = start_transcript(group, (g, h), (&u, &v), context);
+    let (r1, r2, prover) = commit(&transcript.clone().challenge_bytes(b"nonce-context"));
+
+    transcript.append_element(group, b"r1", &r1);
+    transcript.append_element(group, b"r2", &r2);
+    let c = transcript.challenge_scalar(group, b"c");
+
+    Ok(DleqProof {
+        s: prover.solve(secret, &c).to_bytes_be(),
+        c: c.to_bytes_be(),
+    })
+}
+
+/// Verifies a DLEQ proof that `u = g^x` and `v = h^x` for the same `x`, bound
+/// to the given context.
+pub fn verify_dleq_proof<G: PrimeOrderGroup>(
+    group: &G,
+    (g, h): (&G::Element, &G::Element),
+    (u, v): (&G::Element, &G::Element),
+    proof: &DleqProof,
+    context: &[u8],
+) -> Result<bool, Error> {
+    check_bases(group, (g, h))?;
+    check_elements(group, [g, h, u, v])?;
+
+    let c = group.decode_scalar(&proof.c)?;
+    let s = group.decode_scalar(&proof.s)?;
+
+    let r1 = group.double_exp(g, &s, u, &c);
+    let r2 = group.double_exp(h, &s, v, &c);
+
+    let mut transcript = start_transcript(group, (g, h), (u, v), context);
+    transcript.append_element(group, b"r1", &r1);
+    transcript.append_element(group, b"r2", &r2);
+
+    Ok(transcript.challenge_scalar(group, b"c") == c)
+}
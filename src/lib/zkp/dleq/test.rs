@@ -0,0 +1,213 @@
+use crate::{
+    grpc::auth::DleqProof,
+    zkp::{
+        dleq::{start_transcript, verify_dleq_proof},
+        nonce::NonceMode,
+        ristretto::RISTRETTO255_GROUP,
+        signer::Signer,
+        Error, PrimeOrderGroup, MODP_1024_160_GROUP, MODP_2048_256_GROUP,
+    },
+};
+use num_bigint::BigUint;
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+/// Picks two random bases, unrelated to `alpha` and `beta` (as far as the
+/// prover is concerned).
+fn random_bases<G: PrimeOrderGroup>(group: &G) -> (G::Element, G::Element) {
+    (
+        group.exp(group.alpha(), &group.random_scalar()),
+        group.exp(group.beta(), &group.random_scalar()),
+    )
+}
+
+fn test_dleq_proof_for_group<G: PrimeOrderGroup>(group: &G) -> TestResult<()> {
+    let signer = Signer::from(group);
+    let secret = signer.create_random_secret();
+    let (g, h) = random_bases(group);
+    let u = group.exp(&g, secret.expose());
+    let v = group.exp(&h, secret.expose());
+
+    let proof = signer.create_dleq_proof(&secret, (&g, &h), b"context")?;
+
+    assert!(verify_dleq_proof(
+        group,
+        (&g, &h),
+        (&u, &v),
+        &proof,
+        b"context"
+    )?);
+
+    // The proof is bound to the statement and the context.
+    let other_v = group.exp(&h, &(secret.expose() + 1u32));
+    assert!(!verify_dleq_proof(
+        group,
+        (&g, &h),
+        (&u, &other_v),
+        &proof,
+        b"context"
+    )?);
+    assert!(!verify_dleq_proof(
+        group,
+        (&h, &g),
+        (&v, &u),
+        &proof,
+        b"context"
+    )?);
+    assert!(!verify_dleq_proof(
+        group,
+        (&g, &h),
+        (&u, &v),
+        &proof,
+        b"other context"
+    )?);
+
+    Ok(())
+}
+
+#[test]
+fn mod_p_dleq_proof_passes() -> TestResult<()> {
+    test_dleq_proof_for_group(&*MODP_1024_160_GROUP)
+}
+
+#[test]
+fn ristretto255_dleq_proof_passes() -> TestResult<()> {
+    test_dleq_proof_for_group(&*RISTRETTO255_GROUP)
+}
+
+#[test]
+fn hedged_dleq_proof_passes() -> TestResult<()> {
+    let group = &*MODP_2048_256_GROUP;
+    let signer = Signer::from((group, NonceMode::Hedged));
+    let secret = signer.create_random_secret();
+    let (g, h) = random_bases(group);
+    let u = group.exp(&g, secret.expose());
+    let v = group.exp(&h, secret.expose());
+
+    let proof = signer.create_dleq_proof(&secret, (&g, &h), b"context")?;
+
+    assert!(verify_dleq_proof(
+        group,
+        (&g, &h),
+        (&u, &v),
+        &proof,
+        b"context"
+    )?);
+
+    Ok(())
+}
+
+#[test]
+fn dleq_proof_over_alpha_and_beta_matches_the_signature() -> TestResult<()> {
+    // Chaum-Pedersen is the special case with the group's own generators.
+    let group = &*RISTRETTO255_GROUP;
+    let signer = Signer::from(group);
+    let secret = signer.create_random_secret();
+    let signature = signer.create_signature(&secret);
+    let y1 = group.decode(&signature.y1)?;
+    let y2 = group.decode(&signature.y2)?;
+
+    let proof = signer.create_dleq_proof(&secret, (group.alpha(), group.beta()), b"context")?;
+
+    assert!(verify_dleq_proof(
+        group,
+        (group.alpha(), group.beta()),
+        (&y1, &y2),
+        &proof,
+        b"context"
+    )?);
+
+    Ok(())
+}
+
+#[test]
+fn tampered_dleq_proof_is_rejected() -> TestResult<()> {
+    let group = &*MODP_1024_160_GROUP;
+    let signer = Signer::from(group);
+    let secret = signer.create_random_secret();
+    let (g, h) = random_bases(group);
+    let u = group.exp(&g, secret.expose());
+    let v = group.exp(&h, secret.expose());
+
+    let mut proof = signer.create_dleq_proof(&secret, (&g, &h), b"context")?;
+    let s = (BigUint::from_bytes_be(&proof.s) + 1u32) % group.order();
+    proof.s = s.to_bytes_be();
+
+    assert!(!verify_dleq_proof(
+        group,
+        (&g, &h),
+        (&u, &v),
+        &proof,
+        b"context"
+    )?);
+
+    proof.c = group.order().to_bytes_be();
+    assert!(matches!(
+        verify_dleq_proof(group, (&g, &h), (&u, &v), &proof, b"context"),
+        Err(Error::ScalarOutOfRange)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn identity_bases_are_refused() -> TestResult<()> {
+    let group = &*MODP_1024_160_GROUP;
+    let signer = Signer::from(group);
+    let secret = signer.create_random_secret();
+    let identity = group.identity();
+    let (g, _) = random_bases(group);
+
+    assert!(matches!(
+        signer.create_dleq_proof(&secret, (&g, &identity), b"context"),
+        Err(Error::IdentityBase)
+    ));
+
+    // Over the identity, any "secret" would do, so the verifier refuses too.
+    let u = group.exp(&g, secret.expose());
+    let proof = signer.create_dleq_proof(&secret, (&g, &g), b"context")?;
+    assert!(matches!(
+        verify_dleq_proof(group, (&g, &identity), (&u, &identity), &proof, b"context"),
+        Err(Error::IdentityBase)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn public_value_outside_subgroup_is_rejected() -> TestResult<()> {
+    // Publish v = -(h^x), of order 2q, and retry until c is even, so that
+    // h^s * v^c still gives back h^k: without the subgroup check, this false
+    // statement would verify.
+    let group = &*MODP_1024_160_GROUP;
+    let signer = Signer::from(group);
+    let secret = signer.create_random_secret();
+    let (g, h) = random_bases(group);
+    let u = group.exp(&g, secret.expose());
+    let v = &group.p - group.exp(&h, secret.expose());
+
+    let proof = loop {
+        let k = group.random_scalar();
+        let mut transcript = start_transcript(group, (&g, &h), (&u, &v), b"context");
+        transcript.append_element(group, b"r1", &group.exp(&g, &k));
+        transcript.append_element(group, b"r2", &group.exp(&h, &k));
+        let c = transcript.challenge_scalar(group, b"c");
+
+        if !c.bit(0) {
+            let q = group.order();
+            let s = (k + q - (&c * secret.expose()) % q) % q;
+
+            break DleqProof {
+                c: c.to_bytes_be(),
+                s: s.to_bytes_be(),
+            };
+        }
+    };
+
+    assert!(matches!(
+        verify_dleq_proof(group, (&g, &h), (&u, &v), &proof, b"context"),
+        Err(Error::ElementNotInSubgroup)
+    ));
+
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use crate::zkp::GroupId;
 use std::{
     borrow::Cow,
     fmt::{Debug, Display, Formatter, Result as FmtResult},
@@ -6,12 +7,110 @@ use tonic::Status;
 
 pub enum Error {
     GroupNotSpecified,
+    GroupNotRegistered(GroupId),
+    GroupMismatch(GroupId),
+    DuplicateGroup(GroupId),
+    InvalidGroupEncoding(&'static str),
+    InvalidGroupFile(String),
+    PNotPrime,
+    PTooLarge,
+    QNotPrime,
+    QTooSmall,
+    QDoesNotDividePMinusOne,
+    InvalidAlpha,
+    InvalidBeta,
+    BetaNotFromSeed,
+    UnsupportedGroupSize,
+    GroupNotFromSeed,
+    UnsupportedGroupKind,
+    InvalidElement,
+    ProofIncomplete,
+    ChallengeNotBound,
+    EmptySignatureSet,
+    SecretNotInSignatureSet,
+    OrProofSizeMismatch,
+    IdentityBase,
+    InvalidVrfProof,
+    NonCanonicalEncoding,
+    ElementOutOfRange,
+    ElementNotInSubgroup,
+    ScalarOutOfRange,
+    InvalidKdfParams,
+    SaltNotIssued,
+    KdfCostTooLow,
+    SecurityLevelTooLow {
+        group: GroupId,
+        bits: u32,
+        required: u32,
+    },
 }
 
 impl Error {
     fn message(&self) -> Cow<'static, str> {
         match self {
             Self::GroupNotSpecified => Cow::Borrowed("Group not specified"),
+            Self::GroupNotRegistered(id) => Cow::Owned(format!("Group {} is not registered", id)),
+            Self::GroupMismatch(id) => Cow::Owned(format!(
+                "Group parameters do not match registered group {}",
+                id
+            )),
+            Self::DuplicateGroup(id) => Cow::Owned(format!("Group {} is already registered", id)),
+            Self::InvalidGroupEncoding(name) => {
+                Cow::Owned(format!("Failed to decode group parameter {}", name))
+            }
+            Self::InvalidGroupFile(error) => {
+                Cow::Owned(format!("Failed to read group file: {}", error))
+            }
+            Self::PNotPrime => Cow::Borrowed("Group modulus p is not prime"),
+            Self::PTooLarge => Cow::Borrowed("Group modulus p is wider than 8192 bits"),
+            Self::QNotPrime => Cow::Borrowed("Group order q is not prime"),
+            Self::QTooSmall => Cow::Borrowed("Group order q is too small"),
+            Self::QDoesNotDividePMinusOne => Cow::Borrowed("Group order q does not divide p - 1"),
+            Self::InvalidAlpha => Cow::Borrowed("Group generator alpha does not have order q"),
+            Self::InvalidBeta => Cow::Borrowed("Group generator beta does not have order q"),
+            Self::BetaNotFromSeed => {
+                Cow::Borrowed("Group generator beta is not derived from its seed")
+            }
+            Self::UnsupportedGroupSize => {
+                Cow::Borrowed("Group sizes are not supported for generation")
+            }
+            Self::GroupNotFromSeed => {
+                Cow::Borrowed("Group parameters are not generated from their seed")
+            }
+            Self::UnsupportedGroupKind => Cow::Borrowed("Group kind is not supported here"),
+            Self::InvalidElement => Cow::Borrowed("Failed to decode group element"),
+            Self::ProofIncomplete => Cow::Borrowed("Proof requires a commitment and a solution"),
+            Self::ChallengeNotBound => {
+                Cow::Borrowed("Challenge is not bound to this server and account")
+            }
+            Self::EmptySignatureSet => Cow::Borrowed("OR-proof requires at least one signature"),
+            Self::SecretNotInSignatureSet => {
+                Cow::Borrowed("Secret does not match the signature at the given index")
+            }
+            Self::OrProofSizeMismatch => {
+                Cow::Borrowed("OR-proof does not have one branch per signature")
+            }
+            Self::IdentityBase => Cow::Borrowed("Proof bases must not be the identity"),
+            Self::InvalidVrfProof => Cow::Borrowed("VRF proof does not verify"),
+            Self::NonCanonicalEncoding => Cow::Borrowed("Value is not canonically encoded"),
+            Self::ElementOutOfRange => Cow::Borrowed("Group element is not in [1, p - 1]"),
+            Self::ElementNotInSubgroup => {
+                Cow::Borrowed("Group element is not in the order-q subgroup")
+            }
+            Self::ScalarOutOfRange => Cow::Borrowed("Scalar is not in [0, q - 1]"),
+            Self::InvalidKdfParams => Cow::Borrowed("Key derivation parameters are invalid"),
+            Self::SaltNotIssued => Cow::Borrowed("Salt was not issued for this username"),
+            Self::KdfCostTooLow => {
+                Cow::Borrowed("Key derivation cost is below the server's minimum")
+            }
+            Self::SecurityLevelTooLow {
+                group,
+                bits,
+                required,
+            } => Cow::Owned(format!(
+                "Group {} offers about {}-bit security, below the required minimum of {} bits",
+                group, bits, required
+            )),
         }
     }
 }
@@ -32,6 +131,38 @@ impl From<Error> for Status {
     fn from(error: Error) -> Self {
         match error {
             Error::GroupNotSpecified => Self::internal("Group not specified"),
+            Error::GroupNotRegistered(_)
+            | Error::GroupMismatch(_)
+            | Error::InvalidGroupEncoding(_)
+            | Error::InvalidGroupFile(_)
+            | Error::PNotPrime
+            | Error::PTooLarge
+            | Error::QNotPrime
+            | Error::QTooSmall
+            | Error::QDoesNotDividePMinusOne
+            | Error::InvalidAlpha
+            | Error::InvalidBeta
+            | Error::BetaNotFromSeed
+            | Error::UnsupportedGroupSize
+            | Error::GroupNotFromSeed
+            | Error::UnsupportedGroupKind
+            | Error::InvalidElement
+            | Error::ProofIncomplete
+            | Error::ChallengeNotBound
+            | Error::EmptySignatureSet
+            | Error::SecretNotInSignatureSet
+            | Error::OrProofSizeMismatch
+            | Error::IdentityBase
+            | Error::InvalidVrfProof
+            | Error::NonCanonicalEncoding
+            | Error::ElementOutOfRange
+            | Error::ElementNotInSubgroup
+            | Error::ScalarOutOfRange
+            | Error::InvalidKdfParams
+            | Error::SaltNotIssued
+            | Error::KdfCostTooLow => Self::invalid_argument(error.message()),
+            Error::SecurityLevelTooLow { .. } => Self::failed_precondition(error.message()),
+            Error::DuplicateGroup(_) => Self::internal("An internal error occurred"),
         }
     }
 }
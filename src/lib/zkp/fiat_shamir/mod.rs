@@ -0,0 +1,75 @@
+use crate::zkp::{transcript::Transcript, PrimeOrderGroup};
+use num_bigint::BigUint;
+
+#[cfg(test)]
+mod test;
+
+/// The protocol and version that every challenge is domain-separated by, so
+/// that challenges from other protocols (or later versions of this one) never
+/// coincide with ours.
+pub const PROTOCOL_VERSION: &str = "zkp-chaum-pedersen/v1";
+
+/// Who a proof is for: the server that verifies it and the account that it
+/// authenticates. Challenges are bound to both, so a proof (or a solution to a
+/// relayed challenge) can't be replayed against another server or account.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Binding {
+    server_id: String,
+    username: String,
+}
+
+impl Binding {
+    pub fn server_id(&self) -> &str {
+        &self.server_id
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+}
+
+impl From<(&str, &str)> for Binding {
+    fn from((server_id, username): (&str, &str)) -> Self {
+        Self {
+            server_id: server_id.to_string(),
+            username: username.to_string(),
+        }
+    }
+}
+
+/// Starts the transcript of a proof over everything but its commitment.
+pub(super) fn start_transcript<G: PrimeOrderGroup>(
+    group: &G,
+    binding: &Binding,
+    (y1, y2): (&G::Element, &G::Element),
+    context: &[u8],
+) -> Transcript {
+    let mut transcript = Transcript::new(PROTOCOL_VERSION);
+    transcript.append_group(group);
+    transcript.append_message(b"server-id", binding.server_id.as_bytes());
+    transcript.append_message(b"username", binding.username.as_bytes());
+    transcript.append_element(group, b"y1", y1);
+    transcript.append_element(group, b"y2", y2);
+    transcript.append_message(b"context", context);
+
+    transcript
+}
+
+/// Derives a challenge `c` from a transcript of the protocol version, the
+/// group's id and parameters, the binding, the public values `y1` and `y2`, the
+/// context the proof is made in (a timestamp and nonce for Fiat-Shamir proofs,
+/// or the verifier's random nonce for interactive ones), and the commitment
+/// `r1` and `r2`.
+pub fn derive_challenge<G: PrimeOrderGroup>(
+    group: &G,
+    binding: &Binding,
+    (y1, y2): (&G::Element, &G::Element),
+    (r1, r2): (&G::Element, &G::Element),
+    context: &[u8],
+) -> BigUint {
+    let mut transcript = start_transcript(group, binding, (y1, y2), context);
+    transcript.append_element(group, b"r1", r1);
+    transcript.append_element(group, b"r2", r2);
+
+    transcript.challenge_scalar(group, b"c")
+}
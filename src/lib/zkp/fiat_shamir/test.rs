@@ -0,0 +1,65 @@
+use crate::zkp::{
+    fiat_shamir::{derive_challenge, Binding},
+    ristretto::RISTRETTO255_GROUP,
+    PrimeOrderGroup, MODP_1024_160_GROUP,
+};
+use num_bigint::BigUint;
+
+/// Derives a challenge over fixed public values, so that only the binding, the
+/// group and the context vary between calls.
+fn challenge<G: PrimeOrderGroup>(group: &G, binding: &Binding, context: &[u8]) -> BigUint {
+    let y1 = group.exp(group.alpha(), &BigUint::from(3u32));
+    let y2 = group.exp(group.beta(), &BigUint::from(3u32));
+    let r1 = group.exp(group.alpha(), &BigUint::from(5u32));
+    let r2 = group.exp(group.beta(), &BigUint::from(5u32));
+
+    derive_challenge(group, binding, (&y1, &y2), (&r1, &r2), context)
+}
+
+#[test]
+fn challenge_is_deterministic() {
+    let binding = Binding::from(("server", "alice"));
+
+    assert_eq!(
+        challenge(&*MODP_1024_160_GROUP, &binding, b"context"),
+        challenge(&*MODP_1024_160_GROUP, &binding, b"context")
+    );
+}
+
+#[test]
+fn challenge_depends_on_every_binding_field() {
+    let group = &*MODP_1024_160_GROUP;
+    let c = challenge(group, &Binding::from(("server", "alice")), b"context");
+
+    assert_ne!(
+        c,
+        challenge(group, &Binding::from(("other", "alice")), b"context")
+    );
+    assert_ne!(
+        c,
+        challenge(group, &Binding::from(("server", "bob")), b"context")
+    );
+    assert_ne!(
+        c,
+        challenge(group, &Binding::from(("server", "alice")), b"other")
+    );
+}
+
+#[test]
+fn fields_are_length_prefixed() {
+    // Moving bytes from one field into its neighbour must change the challenge.
+    let group = &*MODP_1024_160_GROUP;
+
+    assert_ne!(
+        challenge(group, &Binding::from(("server", "alice")), b"context"),
+        challenge(group, &Binding::from(("servera", "lice")), b"context")
+    );
+}
+
+#[test]
+fn challenge_is_below_the_order() {
+    let binding = Binding::from(("server", "alice"));
+
+    assert!(challenge(&*MODP_1024_160_GROUP, &binding, b"") < *MODP_1024_160_GROUP.order());
+    assert!(challenge(&*RISTRETTO255_GROUP, &binding, b"") < *RISTRETTO255_GROUP.order());
+}
@@ -0,0 +1,195 @@
+//! Verifiable generation of Schnorr groups, following Appendix A of FIPS 186-4
+//! with SHA-512: `p` and `q` are generated from a random seed as in A.1.1.2,
+//! `alpha` is derived from the same seed as in A.2.3, and `beta` is hashed from
+//! the group's beta seed as usual. Anyone holding the seed and counter can redo
+//! the generation and check that the parameters weren't chosen with a backdoor.
+
+use crate::zkp::{
+    constant_time::MAX_MODULUS_BITS,
+    primality::{is_probable_prime, MILLER_RABIN_ROUNDS},
+    Error, Group,
+};
+use num_bigint::BigUint;
+use rand::RngCore;
+use sha2::{Digest, Sha512};
+
+#[cfg(test)]
+mod test;
+
+/// The output length of SHA-512, in bits, which bounds the width of `q`.
+const HASH_BITS: u64 = 512;
+
+/// The seed and counter that a group's `p` and `q` were generated from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DomainParameterSeed {
+    seed: Vec<u8>,
+    counter: u32,
+}
+
+impl DomainParameterSeed {
+    pub fn seed(&self) -> &[u8] {
+        &self.seed
+    }
+
+    pub fn counter(&self) -> u32 {
+        self.counter
+    }
+}
+
+impl From<(Vec<u8>, u32)> for DomainParameterSeed {
+    fn from((seed, counter): (Vec<u8>, u32)) -> Self {
+        Self { seed, counter }
+    }
+}
+
+/// Generates a group with a `p_bits`-bit modulus and a `q_bits`-bit order from
+/// a fresh random seed, with `beta` hashed from the default seed for `id`.
+pub fn generate(id: &str, p_bits: u64, q_bits: u64) -> Result<(Group, DomainParameterSeed), Error> {
+    check_sizes(p_bits, q_bits)?;
+
+    let mut seed = vec![0u8; q_bits.div_ceil(8) as usize];
+
+    let (p, q, counter) = loop {
+        rand::thread_rng().fill_bytes(&mut seed);
+
+        let q = generate_q(&seed, q_bits);
+        if !is_probable_prime(&q, MILLER_RABIN_ROUNDS) {
+            continue;
+        }
+
+        if let Some((p, counter)) = generate_p(&seed, &q, p_bits) {
+            break (p, q, counter);
+        }
+    };
+
+    let alpha = generate_alpha(&seed, &p, &q);
+    let beta_seed = Group::default_beta_seed(id);
+    let beta = Group::hash_to_subgroup(&p, &q, &alpha, beta_seed.as_bytes());
+
+    let seed = DomainParameterSeed::from((seed, counter));
+    let group = Group {
+        domain_parameter_seed: Some(seed.clone()),
+        ..Group::new(id, p, q, alpha, beta, &beta_seed)
+    };
+
+    Ok((group, seed))
+}
+
+/// Redoes the generation of `group` from `seed`, as in Appendix A.1.1.3 of FIPS
+/// 186-4, and checks that it yields the group's `p`, `q` and `alpha`.
+pub fn verify(group: &Group, seed: &DomainParameterSeed) -> Result<(), Error> {
+    let (p_bits, q_bits) = group.bits();
+    check_sizes(p_bits, q_bits)?;
+
+    if (seed.seed.len() as u64) * 8 < q_bits || u64::from(seed.counter) >= 4 * p_bits {
+        return Err(Error::GroupNotFromSeed);
+    }
+
+    let q = generate_q(&seed.seed, q_bits);
+    if q != group.q || !is_probable_prime(&q, MILLER_RABIN_ROUNDS) {
+        return Err(Error::GroupNotFromSeed);
+    }
+
+    // The first prime candidate must be the group's p, found at its counter.
+    match generate_p(&seed.seed, &q, p_bits) {
+        Some((p, counter)) if p == group.p && counter == seed.counter => {}
+        _ => return Err(Error::GroupNotFromSeed),
+    }
+
+    if generate_alpha(&seed.seed, &group.p, &q) != group.alpha {
+        return Err(Error::GroupNotFromSeed);
+    }
+
+    Ok(())
+}
+
+fn check_sizes(p_bits: u64, q_bits: u64) -> Result<(), Error> {
+    if !(3..=HASH_BITS).contains(&q_bits) || p_bits <= q_bits || p_bits > MAX_MODULUS_BITS {
+        return Err(Error::UnsupportedGroupSize);
+    }
+
+    Ok(())
+}
+
+/// Steps 6 and 7 of A.1.1.2: `q = 2^(N - 1) + U + 1 - (U mod 2)`, where `U` is
+/// the seed's hash mod `2^(N - 1)`.
+fn generate_q(seed: &[u8], q_bits: u64) -> BigUint {
+    let top = BigUint::from(1u32) << (q_bits - 1);
+    let u = BigUint::from_bytes_be(&Sha512::digest(seed)) % &top;
+    let parity = if u.bit(0) { 0u32 } else { 1u32 };
+
+    top + u + parity
+}
+
+/// Steps 10 and 11 of A.1.1.2: hashes successive offsets from the seed into
+/// candidates `p = 1 (mod 2q)`, returning the first prime one with its counter,
+/// or nothing if none of the `4L` candidates is prime.
+fn generate_p(seed: &[u8], q: &BigUint, p_bits: u64) -> Option<(BigUint, u32)> {
+    let n = p_bits.div_ceil(HASH_BITS) - 1;
+    let b = p_bits - 1 - n * HASH_BITS;
+    let seed_modulus = BigUint::from(1u32) << (seed.len() * 8);
+    let seed_value = BigUint::from_bytes_be(seed);
+    let top = BigUint::from(1u32) << (p_bits - 1);
+    let two_q = q << 1;
+
+    let mut offset = 1u64;
+
+    for counter in 0..(4 * p_bits) as u32 {
+        // W = V_0 + V_1 * 2^outlen + ... + (V_n mod 2^b) * 2^(n * outlen).
+        let mut w = BigUint::from(0u32);
+
+        for j in 0..=n {
+            let input = (&seed_value + offset + j) % &seed_modulus;
+            let v = BigUint::from_bytes_be(&Sha512::digest(pad(&input, seed.len())));
+            let v = if j == n {
+                v % (BigUint::from(1u32) << b)
+            } else {
+                v
+            };
+
+            w += v << (j * HASH_BITS);
+        }
+
+        let x = w + &top;
+        let c = &x % &two_q;
+        let p = x - c + 1u32;
+
+        if p >= top && is_probable_prime(&p, MILLER_RABIN_ROUNDS) {
+            return Some((p, counter));
+        }
+
+        offset += n + 1;
+    }
+
+    None
+}
+
+/// Appendix A.2.3, with index 1: hashes the seed, "ggen", the index and a count
+/// into `W`, and takes the first `W^((p - 1) / q)` that isn't 0 or 1.
+fn generate_alpha(seed: &[u8], p: &BigUint, q: &BigUint) -> BigUint {
+    const INDEX: u8 = 1;
+
+    let e = (p - 1u32) / q;
+
+    (1u16..)
+        .map(|count| {
+            let mut hasher = Sha512::new();
+            hasher.update(seed);
+            hasher.update(b"ggen");
+            hasher.update([INDEX]);
+            hasher.update(count.to_be_bytes());
+
+            BigUint::from_bytes_be(&hasher.finalize()).modpow(&e, p)
+        })
+        .find(|alpha| *alpha > BigUint::from(1u32))
+        .expect("Ran out of counts")
+}
+
+/// Encodes `n` as exactly `len` big-endian bytes.
+fn pad(n: &BigUint, len: usize) -> Vec<u8> {
+    let bytes = n.to_bytes_be();
+    let mut padded = vec![0u8; len.saturating_sub(bytes.len())];
+    padded.extend_from_slice(&bytes);
+
+    padded
+}
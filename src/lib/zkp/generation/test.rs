@@ -0,0 +1,86 @@
+use crate::zkp::{
+    generation::{generate, verify, DomainParameterSeed},
+    Error, Group, PrimeOrderGroup, MODP_1024_160_GROUP,
+};
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+/// Generates a small group, which is quick but still exercises hashing `p`
+/// from more than one SHA-512 block.
+fn small_group() -> Result<(Group, DomainParameterSeed), Error> {
+    generate("generated", 768, 160)
+}
+
+#[test]
+fn generated_group_is_valid() -> TestResult<()> {
+    let (group, seed) = small_group()?;
+
+    assert_eq!(group.bits(), (768, 160));
+    assert_eq!(seed.seed().len(), 20);
+    group.validate()?;
+
+    Ok(())
+}
+
+#[test]
+fn generated_group_is_verified_from_its_seed() -> TestResult<()> {
+    let (group, seed) = small_group()?;
+
+    verify(&group, &seed)?;
+
+    // The group survives a round trip through its hex encoding.
+    let proto = group.to_proto();
+    let decoded = Group::try_from((
+        group.id(),
+        hex::encode(&proto.p).as_str(),
+        hex::encode(&proto.q).as_str(),
+        hex::encode(&proto.alpha).as_str(),
+    ))?;
+    verify(&decoded, &seed)?;
+
+    Ok(())
+}
+
+#[test]
+fn group_from_another_seed_is_rejected() -> TestResult<()> {
+    let (group, seed) = small_group()?;
+
+    let mut other = seed.seed().to_vec();
+    other[0] ^= 1;
+
+    assert!(matches!(
+        verify(&group, &DomainParameterSeed::from((other, seed.counter()))),
+        Err(Error::GroupNotFromSeed)
+    ));
+    assert!(matches!(
+        verify(
+            &group,
+            &DomainParameterSeed::from((seed.seed().to_vec(), seed.counter() + 1))
+        ),
+        Err(Error::GroupNotFromSeed)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn preset_group_is_not_from_a_generated_seed() -> TestResult<()> {
+    let (_, seed) = small_group()?;
+
+    assert!(matches!(
+        verify(&MODP_1024_160_GROUP, &seed),
+        Err(Error::GroupNotFromSeed)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn unsupported_sizes_are_rejected() {
+    for (p_bits, q_bits) in [(1024, 2), (1024, 1024), (2048, 513), (8256, 256)] {
+        assert!(matches!(
+            generate("generated", p_bits, q_bits),
+            Err(Error::UnsupportedGroupSize)
+        ));
+    }
+}
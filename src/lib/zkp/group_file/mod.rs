@@ -0,0 +1,217 @@
+//! Text formats for groups, so that they can be kept in files. TOML and JSON
+//! hold the hex-encoded parameters along with beta's seed, while PEM wraps the
+//! group's protobuf encoding. Either way, a generated group also carries the
+//! seed and counter it was generated from. Groups are validated whenever
+//! they're loaded, and checked against their seed and counter if they have them.
+
+use crate::{
+    grpc::auth::{GroupKind, ProtoGroup},
+    zkp::{decode_hex, generation::DomainParameterSeed, Error, Group, PrimeOrderGroup},
+};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[cfg(test)]
+mod test;
+
+/// The label of PEM-encoded groups.
+pub const PEM_TAG: &str = "ZKP GROUP";
+
+/// The PEM headers holding a generated group's seed (in hex) and counter.
+const PEM_SEED_HEADER: &str = "Domain-Parameter-Seed";
+const PEM_COUNTER_HEADER: &str = "Counter";
+
+/// A group's hex-encoded parameters, as written to TOML and JSON, and as given
+/// for custom groups in the server config.
+///
+/// `beta` and `beta_seed` may be left out, in which case beta is hashed from
+/// the seed, which defaults to the one derived from the id. Groups made by the
+/// `group` tool also carry the seed and counter that they were generated from,
+/// and are rejected unless redoing the generation gives the same parameters.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupDefinition {
+    pub id: String,
+    pub p: String,
+    pub q: String,
+    pub alpha: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub beta: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub beta_seed: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain_parameter_seed: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub counter: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroupFormat {
+    Toml,
+    Json,
+    Pem,
+}
+
+impl GroupFormat {
+    /// Picks the format from the file's extension.
+    pub fn from_path(path: &Path) -> Result<Self, Error> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Ok(Self::Toml),
+            Some("json") => Ok(Self::Json),
+            Some("pem") => Ok(Self::Pem),
+            _ => Err(Error::InvalidGroupFile(format!(
+                "{} is not a .toml, .json or .pem file",
+                path.display()
+            ))),
+        }
+    }
+}
+
+impl From<Group> for GroupDefinition {
+    fn from(group: Group) -> Self {
+        Self {
+            p: hex::encode_upper(group.p.to_bytes_be()),
+            q: hex::encode_upper(group.q.to_bytes_be()),
+            alpha: hex::encode_upper(group.alpha.to_bytes_be()),
+            beta: Some(hex::encode_upper(group.beta.to_bytes_be())),
+            domain_parameter_seed: group
+                .domain_parameter_seed
+                .as_ref()
+                .map(|seed| hex::encode_upper(seed.seed())),
+            counter: group
+                .domain_parameter_seed
+                .as_ref()
+                .map(DomainParameterSeed::counter),
+            id: group.id,
+            beta_seed: Some(group.beta_seed),
+        }
+    }
+}
+
+impl TryFrom<GroupDefinition> for Group {
+    type Error = Error;
+
+    fn try_from(definition: GroupDefinition) -> Result<Self, Self::Error> {
+        let beta_seed = definition
+            .beta_seed
+            .unwrap_or_else(|| Group::default_beta_seed(&definition.id));
+        let p = decode_hex("p", &definition.p)?;
+        let q = decode_hex("q", &definition.q)?;
+        let alpha = decode_hex("alpha", &definition.alpha)?;
+
+        let group = match definition.beta {
+            Some(beta) => Self::try_from(&ProtoGroup {
+                id: definition.id,
+                kind: GroupKind::Modp.into(),
+                p: p.to_bytes_be(),
+                q: q.to_bytes_be(),
+                alpha: alpha.to_bytes_be(),
+                beta: decode_hex("beta", &beta)?.to_bytes_be(),
+                beta_seed,
+            })?,
+            None => Self::try_from((definition.id.as_str(), p, q, alpha, beta_seed.as_str()))?,
+        };
+
+        with_generation_seed(
+            group,
+            definition.domain_parameter_seed.as_deref(),
+            definition.counter,
+        )
+    }
+}
+
+/// Attaches the hex-encoded seed and the counter that the group was generated
+/// from, if it has them, once they're checked. One without the other is refused.
+fn with_generation_seed(
+    group: Group,
+    seed: Option<&str>,
+    counter: Option<u32>,
+) -> Result<Group, Error> {
+    match (seed, counter) {
+        (Some(seed), Some(counter)) => {
+            let seed = hex::decode(seed.trim())
+                .map_err(|_| Error::InvalidGroupEncoding("domain_parameter_seed"))?;
+
+            group.with_domain_parameter_seed(DomainParameterSeed::from((seed, counter)))
+        }
+        (None, None) => Ok(group),
+        (Some(_), None) => Err(Error::InvalidGroupEncoding("counter")),
+        (None, Some(_)) => Err(Error::InvalidGroupEncoding("domain_parameter_seed")),
+    }
+}
+
+impl Group {
+    pub fn to_text(&self, format: GroupFormat) -> Result<String, Error> {
+        let invalid = |error: String| Error::InvalidGroupFile(error);
+
+        match format {
+            GroupFormat::Toml => toml::to_string(self).map_err(|e| invalid(e.to_string())),
+            GroupFormat::Json => {
+                serde_json::to_string_pretty(self).map_err(|e| invalid(e.to_string()))
+            }
+            GroupFormat::Pem => {
+                let mut pem = pem::Pem::new(PEM_TAG, self.to_proto().encode_to_vec());
+
+                if let Some(seed) = &self.domain_parameter_seed {
+                    let headers = pem.headers_mut();
+                    headers
+                        .add(PEM_SEED_HEADER, &hex::encode_upper(seed.seed()))
+                        .and_then(|_| headers.add(PEM_COUNTER_HEADER, &seed.counter().to_string()))
+                        .map_err(|e| invalid(e.to_string()))?;
+                }
+
+                Ok(pem::encode(&pem))
+            }
+        }
+    }
+
+    /// Parses and validates a group.
+    pub fn from_text(text: &str, format: GroupFormat) -> Result<Self, Error> {
+        let invalid = |error: String| Error::InvalidGroupFile(error);
+
+        match format {
+            GroupFormat::Toml => toml::from_str(text).map_err(|e| invalid(e.to_string())),
+            GroupFormat::Json => serde_json::from_str(text).map_err(|e| invalid(e.to_string())),
+            GroupFormat::Pem => {
+                let pem = pem::parse(text).map_err(|e| invalid(e.to_string()))?;
+                if pem.tag() != PEM_TAG {
+                    return Err(invalid(format!("Expected a {} block", PEM_TAG)));
+                }
+
+                let proto =
+                    ProtoGroup::decode(pem.contents()).map_err(|e| invalid(e.to_string()))?;
+                let counter = pem
+                    .headers()
+                    .get(PEM_COUNTER_HEADER)
+                    .map(|counter| counter.parse())
+                    .transpose()
+                    .map_err(|_| Error::InvalidGroupEncoding("counter"))?;
+
+                with_generation_seed(
+                    Self::try_from(&proto)?,
+                    pem.headers().get(PEM_SEED_HEADER),
+                    counter,
+                )
+            }
+        }
+    }
+
+    /// Reads and validates a group from a file, in the format given by its
+    /// extension.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| Error::InvalidGroupFile(format!("{}: {}", path.display(), e)))?;
+
+        Self::from_text(&text, GroupFormat::from_path(path)?)
+    }
+
+    /// Writes the group to a file, in the format given by its extension.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        let text = self.to_text(GroupFormat::from_path(path)?)?;
+
+        std::fs::write(path, text)
+            .map_err(|e| Error::InvalidGroupFile(format!("{}: {}", path.display(), e)))
+    }
+}
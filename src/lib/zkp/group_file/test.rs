@@ -0,0 +1,154 @@
+use crate::zkp::{
+    generation::generate,
+    group_file::{GroupDefinition, GroupFormat},
+    Error, Group, PrimeOrderGroup, MODP_0005_004_GROUP, MODP_1024_160_GROUP,
+};
+use std::path::Path;
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+const FORMATS: [GroupFormat; 3] = [GroupFormat::Toml, GroupFormat::Json, GroupFormat::Pem];
+
+#[test]
+fn group_survives_every_format() -> TestResult<()> {
+    for group in [&*MODP_0005_004_GROUP, &*MODP_1024_160_GROUP] {
+        for format in FORMATS {
+            let decoded = Group::from_text(&group.to_text(format)?, format)?;
+
+            assert!(decoded.matches(&group.to_proto()), "{:?}", format);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn toml_holds_hex_parameters() -> TestResult<()> {
+    let text = MODP_0005_004_GROUP.to_text(GroupFormat::Toml)?;
+
+    assert_eq!(
+        toml::from_str::<GroupDefinition>(&text)?,
+        GroupDefinition {
+            id: "modp-0005-004".to_string(),
+            p: "17".to_string(),
+            q: "0B".to_string(),
+            alpha: "02".to_string(),
+            beta: Some(hex::encode_upper(MODP_0005_004_GROUP.beta().to_bytes_be())),
+            beta_seed: Some("zkp-chaum-pedersen/modp-0005-004/beta".to_string()),
+            domain_parameter_seed: None,
+            counter: None,
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn group_with_another_beta_is_rejected() -> TestResult<()> {
+    let text = MODP_1024_160_GROUP.to_text(GroupFormat::Json)?;
+    let mut definition: GroupDefinition = serde_json::from_str(&text)?;
+    definition.beta_seed = definition.beta_seed.map(|seed| seed + "/tampered");
+
+    assert!(matches!(
+        Group::try_from(definition),
+        Err(Error::BetaNotFromSeed)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn definition_without_beta_hashes_it_from_the_seed() -> TestResult<()> {
+    let mut definition = GroupDefinition::from(MODP_1024_160_GROUP.clone());
+    definition.beta = None;
+    definition.beta_seed = None;
+
+    assert!(Group::try_from(definition)?.matches(&MODP_1024_160_GROUP.to_proto()));
+
+    Ok(())
+}
+
+#[test]
+fn generation_seed_survives_every_format() -> TestResult<()> {
+    let (group, seed) = generate("generated", 768, 160)?;
+
+    for format in FORMATS {
+        let decoded = Group::from_text(&group.to_text(format)?, format)?;
+
+        assert_eq!(decoded.domain_parameter_seed(), Some(&seed), "{:?}", format);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn group_from_another_generation_seed_is_rejected() -> TestResult<()> {
+    let (group, _) = generate("generated", 768, 160)?;
+    let mut definition = GroupDefinition::from(group);
+    definition.counter = definition.counter.map(|counter| counter + 1);
+
+    assert!(matches!(
+        Group::try_from(definition.clone()),
+        Err(Error::GroupNotFromSeed)
+    ));
+
+    // A seed without a counter can't be checked.
+    definition.counter = None;
+    assert!(matches!(
+        Group::try_from(definition),
+        Err(Error::InvalidGroupEncoding("counter"))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn group_with_composite_p_is_rejected() -> TestResult<()> {
+    let mut definition = GroupDefinition::from(MODP_0005_004_GROUP.clone());
+    // 45 - 1 = 44 = 4 * 11, so q divides p - 1, but p itself is composite.
+    definition.p = "2D".to_string();
+
+    assert!(matches!(Group::try_from(definition), Err(Error::PNotPrime)));
+
+    Ok(())
+}
+
+#[test]
+fn pem_with_another_label_is_rejected() {
+    let text = pem::encode(&pem::Pem::new("CERTIFICATE", vec![0u8; 4]));
+
+    assert!(matches!(
+        Group::from_text(&text, GroupFormat::Pem),
+        Err(Error::InvalidGroupFile(_))
+    ));
+}
+
+#[test]
+fn format_follows_extension() {
+    assert_eq!(
+        GroupFormat::from_path(Path::new("groups/my-group.toml")).ok(),
+        Some(GroupFormat::Toml)
+    );
+    assert_eq!(
+        GroupFormat::from_path(Path::new("my-group.pem")).ok(),
+        Some(GroupFormat::Pem)
+    );
+    assert!(GroupFormat::from_path(Path::new("my-group.yaml")).is_err());
+}
+
+#[test]
+fn group_is_saved_and_loaded() -> TestResult<()> {
+    let dir = std::env::temp_dir().join(format!("zkp-group-file-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+
+    for extension in ["toml", "json", "pem"] {
+        let path = dir.join(format!("group.{}", extension));
+        MODP_1024_160_GROUP.save(&path)?;
+
+        assert!(Group::load(&path)?.matches(&MODP_1024_160_GROUP.to_proto()));
+    }
+
+    std::fs::remove_dir_all(&dir)?;
+
+    Ok(())
+}
@@ -0,0 +1,138 @@
+pub use crate::grpc::auth::KdfParams;
+use crate::zkp::{secret::Secret, Error, PrimeOrderGroup};
+use argon2::{Algorithm, Argon2, Params, Version};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+#[cfg(test)]
+mod test;
+
+/// The length of the salts issued by a `KdfIssuer`, in bytes.
+pub const SALT_LEN: usize = 16;
+
+/// The cost parameters of Argon2id.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+pub struct KdfCost {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for KdfCost {
+    /// OWASP's recommended minimum for Argon2id: 19 MiB, 2 passes and 1 lane.
+    fn default() -> Self {
+        Self {
+            memory_kib: 19 * 1024,
+            iterations: 2,
+            parallelism: 1,
+        }
+    }
+}
+
+impl KdfParams {
+    pub fn cost(&self) -> KdfCost {
+        KdfCost {
+            memory_kib: self.memory_kib,
+            iterations: self.iterations,
+            parallelism: self.parallelism,
+        }
+    }
+}
+
+/// Derives a secret in `[0, q)` from a password with Argon2id.
+///
+/// Argon2id produces 32 more bytes than `q` needs, which are reduced mod `q` in
+/// constant time, leaving a negligible bias.
+pub fn derive_secret<G: PrimeOrderGroup>(
+    group: &G,
+    password: &[u8],
+    params: &KdfParams,
+) -> Result<Secret, Error> {
+    let output_len = group.order().to_bytes_be().len() + 32;
+    let argon2_params = Params::new(
+        params.memory_kib,
+        params.iterations,
+        params.parallelism,
+        Some(output_len),
+    )
+    .map_err(|_| Error::InvalidKdfParams)?;
+
+    let mut output = Zeroizing::new(vec![0u8; output_len]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, argon2_params)
+        .hash_password_into(password, &params.salt, &mut output)
+        .map_err(|_| Error::InvalidKdfParams)?;
+
+    Ok(Secret::new(group.montgomery_q().reduce(&output)))
+}
+
+/// Issues and checks the per-user key derivation parameters on the server.
+///
+/// Each username's salt is an HMAC of the username under a server key, so it is
+/// unique per user without being stored until sign-up, and parameters can be
+/// handed out for unknown usernames without revealing whether they exist.
+#[derive(Clone)]
+pub struct KdfIssuer {
+    key: [u8; 32],
+    cost: KdfCost,
+}
+
+impl KdfIssuer {
+    /// Returns the parameters that a new user with the given name should sign up with.
+    pub fn issue(&self, username: &str) -> KdfParams {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC takes keys of any size");
+        mac.update(username.as_bytes());
+
+        KdfParams {
+            salt: mac.finalize().into_bytes()[..SALT_LEN].to_vec(),
+            memory_kib: self.cost.memory_kib,
+            iterations: self.cost.iterations,
+            parallelism: self.cost.parallelism,
+        }
+    }
+
+    /// Checks that a new user's parameters use the salt issued to them, and cost
+    /// at least as much as the server requires.
+    pub fn check(&self, username: &str, params: &KdfParams) -> Result<(), Error> {
+        if params.salt != self.issue(username).salt {
+            return Err(Error::SaltNotIssued);
+        }
+
+        let cost = params.cost();
+
+        if cost.memory_kib < self.cost.memory_kib
+            || cost.iterations < self.cost.iterations
+            || cost.parallelism < self.cost.parallelism
+        {
+            return Err(Error::KdfCostTooLow);
+        }
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for KdfIssuer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        // Leave the key out of logs.
+        f.debug_struct("KdfIssuer")
+            .field("cost", &self.cost)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for KdfIssuer {
+    fn default() -> Self {
+        Self::from(KdfCost::default())
+    }
+}
+
+impl From<KdfCost> for KdfIssuer {
+    fn from(cost: KdfCost) -> Self {
+        Self {
+            key: rand::random(),
+            cost,
+        }
+    }
+}
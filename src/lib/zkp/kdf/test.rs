@@ -0,0 +1,149 @@
+use crate::zkp::{
+    kdf::{derive_secret, KdfCost, KdfIssuer, KdfParams, SALT_LEN},
+    ristretto::RISTRETTO255_GROUP,
+    Error, PrimeOrderGroup, MODP_1024_160_GROUP,
+};
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+// Cheap enough to keep the tests fast; the server's default is far higher.
+const TEST_COST: KdfCost = KdfCost {
+    memory_kib: 64,
+    iterations: 1,
+    parallelism: 1,
+};
+
+fn test_params(salt: &[u8]) -> KdfParams {
+    KdfParams {
+        salt: salt.to_vec(),
+        memory_kib: TEST_COST.memory_kib,
+        iterations: TEST_COST.iterations,
+        parallelism: TEST_COST.parallelism,
+    }
+}
+
+#[test]
+fn same_password_and_salt_give_the_same_secret() -> TestResult<()> {
+    let group = &*MODP_1024_160_GROUP;
+    let params = test_params(&[1u8; SALT_LEN]);
+
+    let secret = derive_secret(group, b"password", &params)?;
+
+    assert_eq!(
+        derive_secret(group, b"password", &params)?.expose(),
+        secret.expose()
+    );
+    assert!(secret.expose() < group.order());
+
+    Ok(())
+}
+
+#[test]
+fn different_salts_give_different_secrets() -> TestResult<()> {
+    let group = &*RISTRETTO255_GROUP;
+
+    let secret1 = derive_secret(group, b"password", &test_params(&[1u8; SALT_LEN]))?;
+    let secret2 = derive_secret(group, b"password", &test_params(&[2u8; SALT_LEN]))?;
+
+    assert_ne!(secret1.expose(), secret2.expose());
+
+    Ok(())
+}
+
+#[test]
+fn different_costs_give_different_secrets() -> TestResult<()> {
+    let group = &*RISTRETTO255_GROUP;
+    let params = test_params(&[1u8; SALT_LEN]);
+    let costlier = KdfParams {
+        iterations: 2,
+        ..params.clone()
+    };
+
+    assert_ne!(
+        derive_secret(group, b"password", &params)?.expose(),
+        derive_secret(group, b"password", &costlier)?.expose()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn invalid_params_are_rejected() {
+    let group = &*RISTRETTO255_GROUP;
+
+    // Argon2 needs a salt of at least 8 bytes, and at least one pass.
+    let short_salt = test_params(&[1u8; 4]);
+    let no_passes = KdfParams {
+        iterations: 0,
+        ..test_params(&[1u8; SALT_LEN])
+    };
+
+    for params in [short_salt, no_passes] {
+        assert!(matches!(
+            derive_secret(group, b"password", &params),
+            Err(Error::InvalidKdfParams)
+        ));
+    }
+}
+
+#[test]
+fn issued_salts_are_fixed_per_username() {
+    let issuer = KdfIssuer::from(TEST_COST);
+    let alice = issuer.issue("alice");
+
+    assert_eq!(alice.salt.len(), SALT_LEN);
+    assert_eq!(alice.cost(), TEST_COST);
+    assert_eq!(issuer.issue("alice"), alice);
+    assert_ne!(issuer.issue("bob").salt, alice.salt);
+
+    // Another server key gives another salt.
+    assert_ne!(KdfIssuer::from(TEST_COST).issue("alice").salt, alice.salt);
+}
+
+#[test]
+fn issuer_accepts_issued_params() -> TestResult<()> {
+    let issuer = KdfIssuer::from(TEST_COST);
+    let params = issuer.issue("alice");
+    let costlier = KdfParams {
+        memory_kib: 2 * TEST_COST.memory_kib,
+        ..params.clone()
+    };
+
+    issuer.check("alice", &params)?;
+    issuer.check("alice", &costlier)?;
+
+    Ok(())
+}
+
+#[test]
+fn issuer_rejects_salt_issued_to_another_user() {
+    let issuer = KdfIssuer::from(TEST_COST);
+    let params = issuer.issue("bob");
+
+    assert!(matches!(
+        issuer.check("alice", &params),
+        Err(Error::SaltNotIssued)
+    ));
+}
+
+#[test]
+fn issuer_rejects_cheaper_params() {
+    let issuer = KdfIssuer::from(TEST_COST);
+    let params = issuer.issue("alice");
+
+    for cheaper in [
+        KdfParams {
+            memory_kib: TEST_COST.memory_kib - 1,
+            ..params.clone()
+        },
+        KdfParams {
+            iterations: 0,
+            ..params.clone()
+        },
+    ] {
+        assert!(matches!(
+            issuer.check("alice", &cheaper),
+            Err(Error::KdfCostTooLow)
+        ));
+    }
+}
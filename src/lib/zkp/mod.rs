@@ -1,24 +1,78 @@
-use crate::grpc::auth::ProtoGroup;
+use crate::grpc::auth::{GroupKind, ProtoGroup};
+pub use backend::PrimeOrderGroup;
+use constant_time::{FixedBase, Montgomery, MAX_MODULUS_BITS};
 pub use error::Error;
+use generation::DomainParameterSeed;
+use group_file::GroupDefinition;
 use lazy_static::lazy_static;
-use num_bigint::{BigUint, RandBigInt};
+use num_bigint::BigUint;
+use primality::{is_probable_prime, MILLER_RABIN_ROUNDS};
+pub use rfc3526::{
+    MODP_1536_1535_GROUP, MODP_2048_2047_GROUP, MODP_3072_3071_GROUP, MODP_4096_4095_GROUP,
+    MODP_6144_6143_GROUP, MODP_8192_8191_GROUP,
+};
+pub use rfc7919::{
+    FFDHE_2048_GROUP, FFDHE_3072_GROUP, FFDHE_4096_GROUP, FFDHE_6144_GROUP, FFDHE_8192_GROUP,
+};
+use secret::reduce_mod_q;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha512};
+use std::sync::{Arc, OnceLock};
 
+pub mod backend;
+pub mod constant_time;
+pub mod dleq;
 pub mod error;
+pub mod fiat_shamir;
+pub mod generation;
+pub mod group_file;
+pub mod kdf;
+pub mod nonce;
+pub mod or_proof;
+pub mod primality;
+pub mod registry;
+pub mod rfc3526;
+pub mod rfc7919;
+pub mod ristretto;
+pub mod secret;
 pub mod signer;
+pub mod transcript;
 pub mod verifier;
+pub mod vrf;
 
 #[cfg(test)]
 mod test;
 
+/// Ids of the built-in groups that only demonstrate the protocol, and offer no
+/// security at all. They're only built with the `insecure-demo-groups` feature
+/// (or for tests).
+pub const INSECURE_DEMO_GROUP_IDS: [&str; 1] = ["modp-0005-004"];
+
+/// The security level, in bits, of a mod-p group by the width of `p`, as in
+/// Table 2 of NIST SP 800-57 Part 1.
+const NIST_MODULUS_SECURITY_BITS: [(u64, u32); 5] = [
+    (1024, 80),
+    (2048, 112),
+    (3072, 128),
+    (7680, 192),
+    (15360, 256),
+];
+
+// A 5-bit toy group, whose discrete logs can be found by hand.
+#[cfg(any(test, feature = "insecure-demo-groups"))]
 lazy_static! {
     pub static ref MODP_0005_004_GROUP: Group = {
         let p = BigUint::from(23u32);
         let q = BigUint::from(11u32);
         let alpha = BigUint::from(2u32);
 
-        Group::from((p, q, alpha))
+        Group::try_from(("modp-0005-004", p, q, alpha))
+            .expect("Invalid toy group")
+            .with_fixed_base_tables()
     };
+}
 
+lazy_static! {
     // From Section 2.1 of https://www.rfc-editor.org/rfc/rfc5114.txt
     pub static ref MODP_1024_160_GROUP: Group = {
         let p_str = r#"
@@ -43,7 +97,9 @@ lazy_static! {
             855E6EEB 22B3B2E5
         "#;
 
-        Group::from((p_str, q_str, alpha_str))
+        Group::try_from(("rfc5114-modp-1024-160", p_str, q_str, alpha_str))
+            .expect("Invalid RFC 5114 group")
+            .with_fixed_base_tables()
     };
 
     // From Section 2.2 of https://www.rfc-editor.org/rfc/rfc5114.txt
@@ -81,7 +137,9 @@ lazy_static! {
             81BC087F 2A7065B3 84B890D3 191F2BFA
         "#;
 
-        Group::from((p_str, q_str, alpha_str))
+        Group::try_from(("rfc5114-modp-2048-224", p_str, q_str, alpha_str))
+            .expect("Invalid RFC 5114 group")
+            .with_fixed_base_tables()
     };
 
     // From Section 2.3 of https://www.rfc-editor.org/rfc/rfc5114.txt
@@ -119,87 +177,493 @@ lazy_static! {
             5E2327CF EF98C582 664B4C0F 6CC41659
         "#;
 
-        Group::from((p_str, q_str, alpha_str))
+        Group::try_from(("rfc5114-modp-2048-256", p_str, q_str, alpha_str))
+            .expect("Invalid RFC 5114 group")
+            .with_fixed_base_tables()
     };
 }
 
-#[derive(Debug)]
+pub type GroupId = String;
+
+/// Returns the built-in group with the given id, if there is one.
+pub fn preset_group(id: &str) -> Option<&'static Group> {
+    [
+        #[cfg(feature = "insecure-demo-groups")]
+        &*MODP_0005_004_GROUP,
+        &*MODP_1024_160_GROUP,
+        &*MODP_2048_224_GROUP,
+        &*MODP_2048_256_GROUP,
+        &*MODP_1536_1535_GROUP,
+        &*MODP_2048_2047_GROUP,
+        &*MODP_3072_3071_GROUP,
+        &*MODP_4096_4095_GROUP,
+        &*MODP_6144_6143_GROUP,
+        &*MODP_8192_8191_GROUP,
+        &*FFDHE_2048_GROUP,
+        &*FFDHE_3072_GROUP,
+        &*FFDHE_4096_GROUP,
+        &*FFDHE_6144_GROUP,
+        &*FFDHE_8192_GROUP,
+    ]
+    .into_iter()
+    .find(|group| group.id == id)
+}
+
+/// A mod-p group. It (de)serializes as a [`GroupDefinition`], and is validated
+/// when deserialized; see [`group_file`] for reading and writing group files.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(try_from = "GroupDefinition", into = "GroupDefinition")]
 pub struct Group {
+    id: GroupId,
     p: BigUint,
     q: BigUint,
     alpha: BigUint,
     beta: BigUint,
+    /// The seed that `beta` is hashed from, so anyone can recompute it.
+    beta_seed: String,
+    /// The seed and counter that `p`, `q` and `alpha` were generated from, if
+    /// known (and checked).
+    domain_parameter_seed: Option<DomainParameterSeed>,
+    /// `p` and `q`, prepared once for Montgomery arithmetic.
+    montgomery_p: Montgomery,
+    montgomery_q: Montgomery,
+    /// Tables for exponentiating `alpha` and `beta`, if enabled, built on first
+    /// use and shared between clones.
+    fixed_bases: Option<Arc<OnceLock<FixedBases>>>,
+}
+
+/// Precomputed powers of `alpha` and `beta`.
+#[derive(Debug)]
+struct FixedBases {
+    alpha: FixedBase,
+    beta: FixedBase,
 }
 
 impl Group {
-    fn gen_random_beta(p: &BigUint, q: &BigUint, alpha: &BigUint) -> BigUint {
-        loop {
-            let beta = alpha.modpow(&rand::thread_rng().gen_biguint_below(&q), &p);
+    /// Builds a group from parameters that have already been validated.
+    fn new(
+        id: &str,
+        p: BigUint,
+        q: BigUint,
+        alpha: BigUint,
+        beta: BigUint,
+        beta_seed: &str,
+    ) -> Self {
+        Self {
+            id: id.to_string(),
+            montgomery_p: Montgomery::new(&p),
+            montgomery_q: Montgomery::new(&q),
+            p,
+            q,
+            alpha,
+            beta,
+            beta_seed: beta_seed.to_string(),
+            domain_parameter_seed: None,
+            fixed_bases: None,
+        }
+    }
+
+    /// Builds a group from a standardized safe prime `p = 2q + 1`, with 2 as
+    /// `alpha` and `beta` hashed from the default seed. The primality of `p` and
+    /// `q` is taken on trust, since testing it at 8192 bits takes over a minute;
+    /// the presets' tests check it instead.
+    fn from_safe_prime(id: &str, p_str: &str) -> Result<Self, Error> {
+        let p = decode_hex("p", p_str)?;
+        let q = &p >> 1;
+        let alpha = BigUint::from(2u32);
+
+        if p.bits() > MAX_MODULUS_BITS {
+            return Err(Error::PTooLarge);
+        }
 
-            if *alpha != beta {
-                break beta;
+        if q < BigUint::from(3u32) {
+            return Err(Error::QTooSmall);
+        }
+
+        if !p.bit(0) {
+            return Err(Error::QDoesNotDividePMinusOne);
+        }
+
+        if !Self::generates_subgroup(&p, &q, &alpha) {
+            return Err(Error::InvalidAlpha);
+        }
+
+        let beta_seed = Self::default_beta_seed(id);
+        let beta = Self::hash_to_subgroup(&p, &q, &alpha, beta_seed.as_bytes());
+
+        Ok(Self::new(id, p, q, alpha, beta, &beta_seed))
+    }
+
+    /// Returns the seed that `beta` is derived from by default, for a group with
+    /// the given id.
+    pub fn default_beta_seed(id: &str) -> String {
+        format!("zkp-chaum-pedersen/{}/beta", id)
+    }
+
+    /// Hashes `seed` into a generator of the order-`q` subgroup other than
+    /// `alpha`, as in the verifiable generation of Appendix A.2.3 of FIPS 186-4.
+    ///
+    /// For `count = 1, 2, ...`, the seed and count are expanded with SHA-512 to
+    /// 256 bits more than `p`, reduced mod `p` to `h`, and raised to
+    /// `e = (p - 1) / q`; the first `h^e` that isn't 1 (or `alpha`) is `beta`.
+    /// `beta` is then a hash output, so nobody knows `log_alpha(beta)`.
+    fn hash_to_subgroup(p: &BigUint, q: &BigUint, alpha: &BigUint, seed: &[u8]) -> BigUint {
+        let e = (p - 1u32) / q;
+        let len = p.bits().div_ceil(8) as usize + 32;
+
+        (1u32..)
+            .map(|count| {
+                let mut bytes = Vec::with_capacity(len);
+
+                for block in 0u32.. {
+                    if bytes.len() >= len {
+                        break;
+                    }
+
+                    let mut hasher = Sha512::new();
+                    hasher.update(seed);
+                    hasher.update(count.to_be_bytes());
+                    hasher.update(block.to_be_bytes());
+                    bytes.extend_from_slice(&hasher.finalize());
+                }
+
+                (BigUint::from_bytes_be(&bytes[..len]) % p).modpow(&e, p)
+            })
+            .find(|beta| Self::generates_subgroup(p, q, beta) && beta != alpha)
+            .expect("Ran out of counts")
+    }
+
+    /// Checks that the group parameters are mathematically sound, i.e. that `p`
+    /// and `q` are (probably) prime, that `q` divides `p - 1`, and that `alpha`
+    /// and `beta` are distinct generators of the order-`q` subgroup, hashed from
+    /// the group's seed.
+    pub fn validate(&self) -> Result<(), Error> {
+        Self::validate_parameters(&self.p, &self.q, &self.alpha)?;
+        Self::validate_beta(&self.p, &self.q, &self.alpha, &self.beta, &self.beta_seed)
+    }
+
+    fn validate_beta(
+        p: &BigUint,
+        q: &BigUint,
+        alpha: &BigUint,
+        beta: &BigUint,
+        beta_seed: &str,
+    ) -> Result<(), Error> {
+        if !Self::generates_subgroup(p, q, beta) || beta == alpha {
+            return Err(Error::InvalidBeta);
+        }
+
+        if *beta != Self::hash_to_subgroup(p, q, alpha, beta_seed.as_bytes()) {
+            return Err(Error::BetaNotFromSeed);
+        }
+
+        Ok(())
+    }
+
+    fn validate_parameters(p: &BigUint, q: &BigUint, alpha: &BigUint) -> Result<(), Error> {
+        let zero = BigUint::from(0u32);
+        let one = BigUint::from(1u32);
+
+        // Start with the cheap checks, so that bad parameters are rejected quickly.
+        // An order of 2 leaves alpha as the only generator, with no room for beta.
+        if *q < BigUint::from(3u32) {
+            return Err(Error::QTooSmall);
+        }
+
+        if p.bits() > MAX_MODULUS_BITS {
+            return Err(Error::PTooLarge);
+        }
+
+        if *p <= *q || (p - &one) % q != zero {
+            return Err(Error::QDoesNotDividePMinusOne);
+        }
+
+        if !is_probable_prime(q, MILLER_RABIN_ROUNDS) {
+            return Err(Error::QNotPrime);
+        }
+
+        if !is_probable_prime(p, MILLER_RABIN_ROUNDS) {
+            return Err(Error::PNotPrime);
+        }
+
+        if !Self::generates_subgroup(p, q, alpha) {
+            return Err(Error::InvalidAlpha);
+        }
+
+        Ok(())
+    }
+
+    /// Checks that `element` lies in `[2, p - 1]` and has order `q`. Since `q` is
+    /// prime, any such element other than 1 generates the whole subgroup.
+    fn generates_subgroup(p: &BigUint, q: &BigUint, element: &BigUint) -> bool {
+        let one = BigUint::from(1u32);
+
+        *element > one && element < p && element.modpow(q, p) == one
+    }
+
+    /// Returns the bit lengths of `p` and `q`, in that order.
+    pub fn bits(&self) -> (u64, u64) {
+        (self.p.bits(), self.q.bits())
+    }
+
+    pub fn beta_seed(&self) -> &str {
+        &self.beta_seed
+    }
+
+    pub fn domain_parameter_seed(&self) -> Option<&DomainParameterSeed> {
+        self.domain_parameter_seed.as_ref()
+    }
+
+    /// Records the seed and counter that the group was generated from, once
+    /// redoing the generation from them gives the group's parameters.
+    pub fn with_domain_parameter_seed(mut self, seed: DomainParameterSeed) -> Result<Self, Error> {
+        generation::verify(&self, &seed)?;
+        self.domain_parameter_seed = Some(seed);
+
+        Ok(self)
+    }
+
+    /// Makes exponentiations of `alpha` and `beta` use precomputed tables, which
+    /// are built the first time they're needed. Each takes 16 elements per 4 bits
+    /// of `q` (256 KiB for a 2048-bit `p` and 256-bit `q`), so they're best kept
+    /// for long-lived groups.
+    pub fn with_fixed_base_tables(mut self) -> Self {
+        self.fixed_bases = Some(Arc::new(OnceLock::new()));
+        self
+    }
+
+    /// Returns the table for `base`, if it's `alpha` or `beta` and tables are
+    /// enabled.
+    fn fixed_base(&self, base: &BigUint) -> Option<&FixedBase> {
+        let fixed_bases = self.fixed_bases.as_ref()?;
+
+        if *base != self.alpha && *base != self.beta {
+            return None;
+        }
+
+        let fixed_bases = fixed_bases.get_or_init(|| FixedBases {
+            alpha: self.montgomery_p.fixed_base(&self.alpha, self.q.bits()),
+            beta: self.montgomery_p.fixed_base(&self.beta, self.q.bits()),
+        });
+
+        if *base == self.alpha {
+            Some(&fixed_bases.alpha)
+        } else {
+            Some(&fixed_bases.beta)
+        }
+    }
+}
+
+impl PrimeOrderGroup for Group {
+    type Element = BigUint;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn order(&self) -> &BigUint {
+        &self.q
+    }
+
+    /// The cheaper of two attacks: Pollard's rho in the order-`q` subgroup, at
+    /// `q/2` bits, and the number field sieve mod `p`, rated as in NIST SP 800-57
+    /// by the widest listed modulus that `p` reaches (and 0 below 1024 bits).
+    fn security_bits(&self) -> u32 {
+        let nfs = NIST_MODULUS_SECURITY_BITS
+            .iter()
+            .rev()
+            .find(|(p_bits, _)| self.p.bits() >= *p_bits)
+            .map_or(0, |(_, bits)| *bits);
+        let rho = (self.q.bits() / 2) as u32;
+
+        rho.min(nfs)
+    }
+
+    fn alpha(&self) -> &BigUint {
+        &self.alpha
+    }
+
+    fn beta(&self) -> &BigUint {
+        &self.beta
+    }
+
+    fn hash_to_element(&self, input: &[u8]) -> BigUint {
+        Self::hash_to_subgroup(&self.p, &self.q, &self.alpha, input)
+    }
+
+    fn identity(&self) -> BigUint {
+        BigUint::from(1u32)
+    }
+
+    fn montgomery_q(&self) -> &Montgomery {
+        &self.montgomery_q
+    }
+
+    fn exp(&self, base: &BigUint, exponent: &BigUint) -> BigUint {
+        match self.fixed_base(base) {
+            Some(fixed) if exponent.bits() <= fixed.exponent_bits() => {
+                self.montgomery_p.pow_fixed_vartime(fixed, exponent)
             }
+            _ => base.modpow(exponent, &self.p),
+        }
+    }
+
+    fn exp_secret(&self, base: &BigUint, exponent: &BigUint) -> BigUint {
+        let exponent = reduce_mod_q(self, exponent);
+
+        match self.fixed_base(base) {
+            Some(fixed) => self.montgomery_p.pow_fixed(fixed, exponent.expose()),
+            None => self
+                .montgomery_p
+                .pow(base, exponent.expose(), self.q.bits()),
+        }
+    }
+
+    fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        (a * b) % &self.p
+    }
+
+    fn multi_exp(&self, bases: &[BigUint], exponents: &[BigUint]) -> BigUint {
+        self.montgomery_p.multi_pow_vartime(bases, exponents)
+    }
+
+    /// With a table for `g` (i.e. `alpha` or `beta`), `g^a` is read from the
+    /// table, and only `h^b` needs squarings; otherwise both share them.
+    fn double_exp(&self, g: &BigUint, a: &BigUint, h: &BigUint, b: &BigUint) -> BigUint {
+        match self.fixed_base(g) {
+            Some(fixed) if a.bits() <= fixed.exponent_bits() => self.mul(
+                &self.montgomery_p.pow_fixed_vartime(fixed, a),
+                &self
+                    .montgomery_p
+                    .multi_pow_vartime(std::slice::from_ref(h), std::slice::from_ref(b)),
+            ),
+            _ => self.multi_exp(&[g.clone(), h.clone()], &[a.clone(), b.clone()]),
+        }
+    }
+
+    fn check_element(&self, element: &BigUint) -> Result<(), Error> {
+        if *element == BigUint::from(0u32) || *element >= self.p {
+            return Err(Error::ElementOutOfRange);
+        }
+
+        if element.modpow(&self.q, &self.p) != BigUint::from(1u32) {
+            return Err(Error::ElementNotInSubgroup);
+        }
+
+        Ok(())
+    }
+
+    fn encode(&self, element: &BigUint) -> Vec<u8> {
+        element.to_bytes_be()
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<BigUint, Error> {
+        if bytes.is_empty() || (bytes.len() > 1 && bytes[0] == 0) {
+            return Err(Error::NonCanonicalEncoding);
         }
+
+        let element = BigUint::from_bytes_be(bytes);
+        self.check_element(&element)?;
+
+        Ok(element)
     }
 
     fn to_proto(&self) -> ProtoGroup {
         ProtoGroup {
+            id: self.id.clone(),
+            kind: GroupKind::Modp.into(),
             p: self.p.to_bytes_be(),
             q: self.q.to_bytes_be(),
             alpha: self.alpha.to_bytes_be(),
             beta: self.beta.to_bytes_be(),
+            beta_seed: self.beta_seed.clone(),
         }
     }
 }
 
-impl From<&ProtoGroup> for Group {
-    fn from(group: &ProtoGroup) -> Self {
-        Self {
-            p: BigUint::from_bytes_be(group.p.as_slice()),
-            q: BigUint::from_bytes_be(group.q.as_slice()),
-            alpha: BigUint::from_bytes_be(group.alpha.as_slice()),
-            beta: BigUint::from_bytes_be(group.beta.as_slice()),
+impl TryFrom<&ProtoGroup> for Group {
+    type Error = Error;
+
+    fn try_from(group: &ProtoGroup) -> Result<Self, Self::Error> {
+        if group.kind() != GroupKind::Modp {
+            return Err(Error::UnsupportedGroupKind);
         }
+
+        let p = BigUint::from_bytes_be(group.p.as_slice());
+        let q = BigUint::from_bytes_be(group.q.as_slice());
+        let alpha = BigUint::from_bytes_be(group.alpha.as_slice());
+        let beta = BigUint::from_bytes_be(group.beta.as_slice());
+
+        // Validate before preparing p and q, which must be odd.
+        Self::validate_parameters(&p, &q, &alpha)?;
+        Self::validate_beta(&p, &q, &alpha, &beta, &group.beta_seed)?;
+
+        Ok(Self::new(&group.id, p, q, alpha, beta, &group.beta_seed))
+    }
+}
+
+impl TryFrom<(&str, BigUint, BigUint, BigUint, &str)> for Group {
+    type Error = Error;
+
+    fn try_from(
+        (id, p, q, alpha, beta_seed): (&str, BigUint, BigUint, BigUint, &str),
+    ) -> Result<Self, Self::Error> {
+        // Beta is hashed into the subgroup, so it's valid as long as p, q and
+        // alpha are.
+        Self::validate_parameters(&p, &q, &alpha)?;
+
+        let beta = Self::hash_to_subgroup(&p, &q, &alpha, beta_seed.as_bytes());
+
+        Ok(Self::new(id, p, q, alpha, beta, beta_seed))
     }
 }
 
-impl From<(BigUint, BigUint, BigUint)> for Group {
-    fn from((p, q, alpha): (BigUint, BigUint, BigUint)) -> Self {
-        let beta = Self::gen_random_beta(&p, &q, &alpha);
+impl TryFrom<(&str, BigUint, BigUint, BigUint)> for Group {
+    type Error = Error;
 
-        Self { p, q, alpha, beta }
+    fn try_from((id, p, q, alpha): (&str, BigUint, BigUint, BigUint)) -> Result<Self, Self::Error> {
+        Self::try_from((id, p, q, alpha, Self::default_beta_seed(id).as_str()))
     }
 }
 
-impl From<(&'static str, &'static str, &'static str)> for Group {
-    fn from((p_str, q_str, alpha_str): (&'static str, &'static str, &'static str)) -> Self {
-        let p_bytes = hex::decode(
-            p_str
-                .chars()
-                .filter(|c| !c.is_whitespace())
-                .collect::<String>(),
-        )
-        .expect("Failed to decode p");
-        let q_bytes = hex::decode(
-            q_str
-                .chars()
-                .filter(|c| !c.is_whitespace())
-                .collect::<String>(),
-        )
-        .expect("Failed to decode q");
-        let alpha_bytes = hex::decode(
-            alpha_str
-                .chars()
-                .filter(|c| !c.is_whitespace())
-                .collect::<String>(),
-        )
-        .expect("Failed to decode alpha");
-
-        let p = BigUint::from_bytes_be(&p_bytes);
-        let q = BigUint::from_bytes_be(&q_bytes);
-        let alpha = BigUint::from_bytes_be(&alpha_bytes);
-        let beta = Self::gen_random_beta(&p, &q, &alpha);
-
-        Self { p, q, alpha, beta }
+/// Decodes a big-endian hex string, ignoring any whitespace.
+fn decode_hex(name: &'static str, hex_str: &str) -> Result<BigUint, Error> {
+    hex::decode(
+        hex_str
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>(),
+    )
+    .map(|bytes| BigUint::from_bytes_be(&bytes))
+    .map_err(|_| Error::InvalidGroupEncoding(name))
+}
+
+impl TryFrom<(&str, &str, &str, &str, &str)> for Group {
+    type Error = Error;
+
+    fn try_from(
+        (id, p_str, q_str, alpha_str, beta_seed): (&str, &str, &str, &str, &str),
+    ) -> Result<Self, Self::Error> {
+        let p = decode_hex("p", p_str)?;
+        let q = decode_hex("q", q_str)?;
+        let alpha = decode_hex("alpha", alpha_str)?;
+
+        Self::try_from((id, p, q, alpha, beta_seed))
+    }
+}
+
+impl TryFrom<(&str, &str, &str, &str)> for Group {
+    type Error = Error;
+
+    fn try_from(
+        (id, p_str, q_str, alpha_str): (&str, &str, &str, &str),
+    ) -> Result<Self, Self::Error> {
+        Self::try_from((
+            id,
+            p_str,
+            q_str,
+            alpha_str,
+            Self::default_beta_seed(id).as_str(),
+        ))
     }
 }
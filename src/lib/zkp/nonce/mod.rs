@@ -0,0 +1,145 @@
+use crate::zkp::{secret::Secret, PrimeOrderGroup};
+use hmac::{
+    digest::{core_api::BlockSizeUser, Digest},
+    Mac, SimpleHmac,
+};
+use num_bigint::BigUint;
+use prost::Message;
+use rand::RngCore;
+use sha2::Sha512;
+use zeroize::Zeroizing;
+
+#[cfg(test)]
+mod test;
+
+/// The number of bytes of fresh entropy mixed into each hedged nonce.
+pub const NONCE_ENTROPY_LEN: usize = 32;
+
+/// How a `Signer` picks the nonce `k` behind each commitment.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NonceMode {
+    /// Draw `k` straight from the thread-local RNG.
+    #[default]
+    Random,
+
+    /// Derive `k` deterministically from the secret, the group and the context,
+    /// mixed with fresh entropy (see [`derive_nonce`]). A weak or repeated RNG
+    /// output then can't leak the secret, while the entropy still protects
+    /// against fault attacks on a purely deterministic nonce.
+    ///
+    /// Only non-interactive proofs are hedged, as their context includes the
+    /// statement being proven. Interactive commitments are made before there's
+    /// any context, so they always draw `k` from the RNG.
+    Hedged,
+}
+
+/// Derives a hedged nonce `k` in `[1, q)` for proving knowledge of `secret` in
+/// the given context, as in Section 3.6 of RFC 6979 (with HMAC-SHA-512).
+///
+/// The "message" is a length-prefixed hash of the group and the context, and
+/// `entropy` is appended as the additional data `k'`. With empty entropy this is
+/// plain deterministic RFC 6979; with fresh entropy every call gives a new `k`,
+/// but a broken RNG degrades to the deterministic case rather than to a
+/// predictable or repeated `k`.
+pub fn derive_nonce<G: PrimeOrderGroup>(
+    group: &G,
+    secret: &Secret,
+    context: &[u8],
+    entropy: &[u8],
+) -> Secret {
+    let mut hasher = Sha512::new();
+
+    for input in [group.to_proto().encode_to_vec(), context.to_vec()] {
+        hasher.update((input.len() as u64).to_be_bytes());
+        hasher.update(input);
+    }
+
+    Secret::new(rfc6979_nonce::<Sha512>(
+        group.order(),
+        secret.expose(),
+        &hasher.finalize(),
+        entropy,
+    ))
+}
+
+/// Returns [`NONCE_ENTROPY_LEN`] bytes of fresh entropy for [`derive_nonce`].
+pub fn fresh_entropy() -> [u8; NONCE_ENTROPY_LEN] {
+    let mut entropy = [0u8; NONCE_ENTROPY_LEN];
+    rand::thread_rng().fill_bytes(&mut entropy);
+
+    entropy
+}
+
+/// Generates `k` per Section 3.2 of RFC 6979 for the order `q`, the secret `x`
+/// and the message hash `h1`, with `extra` as the additional data of Section 3.6.
+pub fn rfc6979_nonce<D>(q: &BigUint, x: &BigUint, h1: &[u8], extra: &[u8]) -> BigUint
+where
+    D: Digest + BlockSizeUser + Clone,
+{
+    let qlen = q.bits();
+    let rlen = qlen.div_ceil(8) as usize;
+    let hlen = <D as Digest>::output_size();
+
+    // Everything derived from x here determines k, so it's all zeroized.
+    let hmac = |key: &[u8], parts: &[&[u8]]| -> Zeroizing<Vec<u8>> {
+        let mut mac = SimpleHmac::<D>::new_from_slice(key).expect("HMAC takes keys of any size");
+
+        for part in parts {
+            mac.update(part);
+        }
+
+        Zeroizing::new(mac.finalize().into_bytes().to_vec())
+    };
+
+    let x = Zeroizing::new(int2octets(x, rlen));
+    let h1 = int2octets(&(bits2int(h1, qlen) % q), rlen);
+
+    // Steps b. to g.
+    let mut v = Zeroizing::new(vec![0x01u8; hlen]);
+    let mut k = Zeroizing::new(vec![0x00u8; hlen]);
+
+    k = hmac(&k, &[&v, &[0x00], &x, &h1, extra]);
+    v = hmac(&k, &[&v]);
+    k = hmac(&k, &[&v, &[0x01], &x, &h1, extra]);
+    v = hmac(&k, &[&v]);
+
+    // Step h.
+    loop {
+        let mut t = Zeroizing::new(Vec::with_capacity(rlen + hlen));
+
+        while t.len() < rlen {
+            v = hmac(&k, &[&v]);
+            t.extend_from_slice(&v);
+        }
+
+        let nonce = bits2int(&t, qlen);
+
+        if nonce > BigUint::from(0u32) && nonce < *q {
+            break nonce;
+        }
+
+        k = hmac(&k, &[&v, &[0x00]]);
+        v = hmac(&k, &[&v]);
+    }
+}
+
+/// Interprets the leftmost `qlen` bits of `bytes` as an integer (Section 2.3.2).
+fn bits2int(bytes: &[u8], qlen: u64) -> BigUint {
+    let value = BigUint::from_bytes_be(bytes);
+    let blen = bytes.len() as u64 * 8;
+
+    if blen > qlen {
+        value >> (blen - qlen)
+    } else {
+        value
+    }
+}
+
+/// Encodes `value` as exactly `rlen` big-endian bytes (Section 2.3.3).
+fn int2octets(value: &BigUint, rlen: usize) -> Vec<u8> {
+    let bytes = value.to_bytes_be();
+    let mut octets = vec![0u8; rlen.saturating_sub(bytes.len())];
+    octets.extend_from_slice(&bytes);
+
+    octets
+}
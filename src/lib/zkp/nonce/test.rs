@@ -0,0 +1,102 @@
+use crate::zkp::{
+    nonce::{derive_nonce, fresh_entropy, rfc6979_nonce},
+    ristretto::RISTRETTO255_GROUP,
+    secret::Secret,
+    test::hex_int,
+};
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256, Sha512};
+
+// The DSA 1024-bit key from Appendix A.2.1 of RFC 6979.
+fn rfc6979_key() -> (BigUint, BigUint) {
+    let q = hex_int("996F967F6C8E388D9E28D01E205FBA957A5698B1");
+    let x = hex_int("411602CB19A6CCC34494D79D98EF1E7ED5AF25F7");
+
+    (q, x)
+}
+
+fn ristretto_secret() -> Secret {
+    Secret::new(hex_int(
+        "0aa1ae27ac31820484bfc443fe67dbb13205cef806dd282753d5558fd8e12874",
+    ))
+}
+
+#[test]
+fn rfc6979_sha256_vectors() {
+    let (q, x) = rfc6979_key();
+
+    for (message, k) in [
+        ("sample", "519BA0546D0C39202A7D34D7DFA5E760B318BCFB"),
+        ("test", "5A67592E8128E03A417B0484410FB72C0B630E1A"),
+    ] {
+        let h1 = Sha256::digest(message);
+
+        assert_eq!(rfc6979_nonce::<Sha256>(&q, &x, &h1, &[]), hex_int(k));
+    }
+}
+
+#[test]
+fn rfc6979_sha512_vectors() {
+    let (q, x) = rfc6979_key();
+
+    for (message, k) in [
+        ("sample", "09ECE7CA27D0F5A4DD4E556C9DF1D21D28104F8B"),
+        ("test", "65D2C2EEB175E370F28C75BFCDC028D22C7DBE9C"),
+    ] {
+        let h1 = Sha512::digest(message);
+
+        assert_eq!(rfc6979_nonce::<Sha512>(&q, &x, &h1, &[]), hex_int(k));
+    }
+}
+
+#[test]
+fn rfc6979_additional_data_vector() {
+    let (q, x) = rfc6979_key();
+    let h1 = Sha512::digest("sample");
+
+    assert_eq!(
+        rfc6979_nonce::<Sha512>(&q, &x, &h1, b"extra"),
+        hex_int("3A91613BE3EA575AAE6A97BA8DD91CD3F860CC1E")
+    );
+}
+
+#[test]
+fn derive_nonce_vectors() {
+    let group = &*RISTRETTO255_GROUP;
+    let secret = ristretto_secret();
+    let entropy: Vec<u8> = (0u8..32).collect();
+
+    for (context, entropy, k) in [
+        (
+            &b"context"[..],
+            &[][..],
+            "0c7798e82f10692c9350f42ad4fa14b4c7085438b157ef7a9db5f53b5b03d34e",
+        ),
+        (
+            b"context",
+            &entropy,
+            "0b9a0e7bd5fe13d766f5e981a2f9031d51fea6f5b6bbc65a43600a0e448372d3",
+        ),
+        (
+            b"other context",
+            &[],
+            "0b99540ff3e117462b5153d7723312772acb2ad75773527790ff13a7db367eb0",
+        ),
+    ] {
+        assert_eq!(
+            *derive_nonce(group, &secret, context, entropy).expose(),
+            hex_int(k)
+        );
+    }
+}
+
+#[test]
+fn fresh_entropy_gives_fresh_nonces() {
+    let group = &*RISTRETTO255_GROUP;
+    let secret = ristretto_secret();
+
+    let k1 = derive_nonce(group, &secret, b"context", &fresh_entropy());
+    let k2 = derive_nonce(group, &secret, b"context", &fresh_entropy());
+
+    assert_ne!(k1.expose(), k2.expose());
+}
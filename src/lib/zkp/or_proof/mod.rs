@@ -0,0 +1,230 @@
+//! 1-of-N OR-proofs (the Cramer-Damgård-Schoenmakers composition): a proof of
+//! knowing the secret behind one of several signatures, without revealing
+//! which, so that a member of a group of users can log in anonymously.
+//!
+//! The prover answers the real branch honestly and simulates every other one by
+//! picking its challenge and solution first. The challenges must sum to the
+//! challenge hashed from the whole transcript, so only one branch can have been
+//! simulated after the fact, and the verifier can't tell which.
+
+use crate::{
+    grpc::auth::{Commitment, OrProof, OrProofBranch, Signature, Solution},
+    zkp::{
+        fiat_shamir::Binding,
+        secret::Secret,
+        signer::ProverState,
+        transcript::Transcript,
+        verifier::{check_group, check_solution},
+        Error, PrimeOrderGroup,
+    },
+};
+use num_bigint::BigUint;
+
+#[cfg(test)]
+mod test;
+
+/// The protocol and version that OR-proof transcripts are domain-separated by.
+pub const OR_PROTOCOL_VERSION: &str = "zkp-chaum-pedersen-or/v1";
+
+/// The public values `(y1, y2)` of a signature.
+type PublicValues<G> = (
+    <G as PrimeOrderGroup>::Element,
+    <G as PrimeOrderGroup>::Element,
+);
+
+/// One decoded branch of an OR-proof: the commitment `(r1, r2)`, the challenge
+/// `c` and the solution `s`.
+struct Branch<G: PrimeOrderGroup> {
+    r1: G::Element,
+    r2: G::Element,
+    c: BigUint,
+    s: BigUint,
+}
+
+/// Sums the branches' challenges mod `q`.
+fn sum_challenges<G: PrimeOrderGroup>(group: &G, branches: &[Branch<G>]) -> BigUint {
+    branches.iter().fold(BigUint::from(0u32), |sum, branch| {
+        (sum + &branch.c) % group.order()
+    })
+}
+
+/// Decodes the public values `(y1, y2)` of every signature, each of which must
+/// have been made against the given group.
+fn decode_signatures<G: PrimeOrderGroup>(
+    group: &G,
+    signatures: &[Signature],
+) -> Result<Vec<PublicValues<G>>, Error> {
+    if signatures.is_empty() {
+        return Err(Error::EmptySignatureSet);
+    }
+
+    signatures
+        .iter()
+        .map(|signature| {
+            check_group(group, signature)?;
+
+            Ok((group.decode(&signature.y1)?, group.decode(&signature.y2)?))
+        })
+        .collect()
+}
+
+/// Starts the transcript of an OR-proof over everything but its commitments.
+fn start_transcript<G: PrimeOrderGroup>(
+    group: &G,
+    ys: &[PublicValues<G>],
+    binding: &Binding,
+    context: &[u8],
+) -> Transcript {
+    let mut transcript = Transcript::new(OR_PROTOCOL_VERSION);
+    transcript.append_group(group);
+    transcript.append_message(b"server-id", binding.server_id().as_bytes());
+    transcript.append_message(b"username", binding.username().as_bytes());
+    transcript.append_message(b"size", &(ys.len() as u64).to_be_bytes());
+
+    for (y1, y2) in ys {
+        transcript.append_element(group, b"y1", y1);
+        transcript.append_element(group, b"y2", y2);
+    }
+
+    transcript.append_message(b"context", context);
+
+    transcript
+}
+
+/// Creates an OR-proof for the signature at `index`, whose secret is `secret`.
+/// `commit` draws the real branch's nonce, and `draw` each simulated branch's
+/// challenge and solution, given a context that covers the whole statement, so
+/// that a hedged nonce is never reused across statements.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn prove<'a, G: PrimeOrderGroup>(
+    group: &'a G,
+    secret: &Secret,
+    signatures: &[Signature],
+    index: usize,
+    binding: &Binding,
+    context: &[u8],
+    commit: impl FnOnce(&[u8]) -> (G::Element, G::Element, ProverState<'a, G>),
+    draw: impl Fn(&[u8]) -> Secret,
+) -> Result<OrProof, Error> {
+    let ys = decode_signatures(group, signatures)?;
+
+    match ys.get(index) {
+        Some((y1, y2))
+            if *y1 == group.exp_secret(group.alpha(), secret.expose())
+                && *y2 == group.exp_secret(group.beta(), secret.expose()) => {}
+        _ => return Err(Error::SecretNotInSignatureSet),
+    }
+
+    let mut transcript = start_transcript(group, &ys, binding, context);
+    let (r1, r2, prover) = commit(&transcript.clone().challenge_bytes(b"nonce-context"));
+
+    // Simulate every other branch: pick c and s, then solve for the commitment.
+    let mut branches: Vec<Branch<G>> = ys
+        .iter()
+        .enumerate()
+        .map(|(i, (y1, y2))| {
+            if i == index {
+                let zero = BigUint::from(0u32);
+
+                return Branch {
+                    r1: r1.clone(),
+                    r2: r2.clone(),
+                    c: zero.clone(),
+                    s: zero,
+                };
+            }
+
+            let simulated_context = |label: &'static [u8]| {
+                let mut nonce_transcript = transcript.clone();
+                nonce_transcript.append_message(b"branch", &(i as u64).to_be_bytes());
+                nonce_transcript.challenge_bytes(label)
+            };
+            let c = draw(&simulated_context(b"simulated-c")).expose().clone();
+            let s = draw(&simulated_context(b"simulated-s")).expose().clone();
+
+            Branch {
+                r1: group.double_exp(group.alpha(), &s, y1, &c),
+                r2: group.double_exp(group.beta(), &s, y2, &c),
+                c,
+                s,
+            }
+        })
+        .collect();
+
+    for branch in branches.iter() {
+        transcript.append_element(group, b"r1", &branch.r1);
+        transcript.append_element(group, b"r2", &branch.r2);
+    }
+
+    // The real branch's challenge is whatever makes them all sum to c.
+    let q = group.order();
+    let c = transcript.challenge_scalar(group, b"c");
+    let c_real = (c + q - sum_challenges(group, &branches)) % q;
+
+    branches[index].s = prover.solve(secret, &c_real);
+    branches[index].c = c_real;
+
+    Ok(OrProof {
+        branches: branches
+            .into_iter()
+            .map(|branch| OrProofBranch {
+                commitment: Some(Commitment {
+                    r1: group.encode(&branch.r1),
+                    r2: group.encode(&branch.r2),
+                }),
+                c: branch.c.to_bytes_be(),
+                solution: Some(Solution {
+                    s: branch.s.to_bytes_be(),
+                }),
+            })
+            .collect(),
+    })
+}
+
+/// Verifies an OR-proof that the prover knows the secret behind one of the
+/// given signatures, bound to the given server, account and context: every
+/// branch must check out, and their challenges must sum to the challenge hashed
+/// from the transcript.
+pub fn verify_or_proof<G: PrimeOrderGroup>(
+    group: &G,
+    signatures: &[Signature],
+    proof: &OrProof,
+    binding: &Binding,
+    context: &[u8],
+) -> Result<bool, Error> {
+    let ys = decode_signatures(group, signatures)?;
+
+    if proof.branches.len() != ys.len() {
+        return Err(Error::OrProofSizeMismatch);
+    }
+
+    let mut transcript = start_transcript(group, &ys, binding, context);
+    let mut branches = Vec::with_capacity(ys.len());
+
+    for branch in proof.branches.iter() {
+        let commitment = branch.commitment.as_ref().ok_or(Error::ProofIncomplete)?;
+        let solution = branch.solution.as_ref().ok_or(Error::ProofIncomplete)?;
+
+        let r1 = group.decode(&commitment.r1)?;
+        let r2 = group.decode(&commitment.r2)?;
+        let c = group.decode_scalar(&branch.c)?;
+        let s = group.decode_scalar(&solution.s)?;
+
+        transcript.append_element(group, b"r1", &r1);
+        transcript.append_element(group, b"r2", &r2);
+        branches.push(Branch { r1, r2, c, s });
+    }
+
+    let c = transcript.challenge_scalar(group, b"c");
+
+    Ok(sum_challenges(group, &branches) == c
+        && ys.iter().zip(branches.iter()).all(|((y1, y2), branch)| {
+            check_solution(
+                group,
+                (y1, y2),
+                (&branch.r1, &branch.r2),
+                &branch.c,
+                &branch.s,
+            )
+        }))
+}
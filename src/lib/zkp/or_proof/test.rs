@@ -0,0 +1,229 @@
+use crate::{
+    grpc::auth::Signature,
+    zkp::{
+        fiat_shamir::Binding, nonce::NonceMode, or_proof::verify_or_proof,
+        ristretto::RISTRETTO255_GROUP, secret::Secret, signer::Signer, test::binding, Error,
+        PrimeOrderGroup, MODP_1024_160_GROUP, MODP_2048_256_GROUP,
+    },
+};
+use num_bigint::BigUint;
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+const SIZE: usize = 4;
+
+/// Creates `SIZE` signatures, each from a different secret.
+fn members<G: PrimeOrderGroup>(signer: &Signer<G>) -> (Vec<Secret>, Vec<Signature>) {
+    let secrets: Vec<Secret> = (0..SIZE).map(|_| signer.create_random_secret()).collect();
+    let signatures = secrets
+        .iter()
+        .map(|secret| signer.create_signature(secret))
+        .collect();
+
+    (secrets, signatures)
+}
+
+fn test_or_proof_for_group<G: PrimeOrderGroup>(group: &G) -> TestResult<()> {
+    let signer = Signer::from(group);
+    let (secrets, signatures) = members(&signer);
+
+    // Any member can prove membership, whatever their position.
+    for (index, secret) in secrets.iter().enumerate() {
+        let proof = signer.create_or_proof(secret, &signatures, index, &binding(), b"context")?;
+
+        assert!(verify_or_proof(
+            group,
+            &signatures,
+            &proof,
+            &binding(),
+            b"context"
+        )?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn mod_p_or_proof_passes() -> TestResult<()> {
+    test_or_proof_for_group(&*MODP_1024_160_GROUP)
+}
+
+#[test]
+fn ristretto255_or_proof_passes() -> TestResult<()> {
+    test_or_proof_for_group(&*RISTRETTO255_GROUP)
+}
+
+#[test]
+fn hedged_or_proof_passes() -> TestResult<()> {
+    let group = &*MODP_2048_256_GROUP;
+    let signer = Signer::from((group, NonceMode::Hedged));
+    let (secrets, signatures) = members(&signer);
+    let proof = signer.create_or_proof(&secrets[2], &signatures, 2, &binding(), b"context")?;
+    let other = signer.create_or_proof(&secrets[2], &signatures, 2, &binding(), b"context")?;
+
+    assert!(verify_or_proof(
+        group,
+        &signatures,
+        &proof,
+        &binding(),
+        b"context"
+    )?);
+
+    // The simulated branches are hedged too, so the fresh entropy gives them
+    // different challenges every time.
+    assert_ne!(proof.branches[0].c, other.branches[0].c);
+
+    Ok(())
+}
+
+#[test]
+fn single_signature_or_proof_passes() -> TestResult<()> {
+    let group = &*MODP_1024_160_GROUP;
+    let signer = Signer::from(group);
+    let secret = signer.create_random_secret();
+    let signatures = vec![signer.create_signature(&secret)];
+    let proof = signer.create_or_proof(&secret, &signatures, 0, &binding(), b"context")?;
+
+    assert!(verify_or_proof(
+        group,
+        &signatures,
+        &proof,
+        &binding(),
+        b"context"
+    )?);
+
+    Ok(())
+}
+
+#[test]
+fn or_proof_for_another_binding_or_context_is_rejected() -> TestResult<()> {
+    let group = &*RISTRETTO255_GROUP;
+    let signer = Signer::from(group);
+    let (secrets, signatures) = members(&signer);
+    let proof = signer.create_or_proof(&secrets[1], &signatures, 1, &binding(), b"context")?;
+
+    let other = Binding::from(("other-server", "members"));
+    assert!(!verify_or_proof(
+        group,
+        &signatures,
+        &proof,
+        &other,
+        b"context"
+    )?);
+    assert!(!verify_or_proof(
+        group,
+        &signatures,
+        &proof,
+        &binding(),
+        b"other context"
+    )?);
+
+    Ok(())
+}
+
+#[test]
+fn or_proof_for_other_signatures_is_rejected() -> TestResult<()> {
+    let group = &*MODP_1024_160_GROUP;
+    let signer = Signer::from(group);
+    let (secrets, mut signatures) = members(&signer);
+    let proof = signer.create_or_proof(&secrets[0], &signatures, 0, &binding(), b"context")?;
+
+    // Swapping two members changes the statement, even though the set is the same.
+    signatures.swap(1, 2);
+    assert!(!verify_or_proof(
+        group,
+        &signatures,
+        &proof,
+        &binding(),
+        b"context"
+    )?);
+
+    // Replacing the prover's own signature with a stranger's.
+    signatures.swap(1, 2);
+    signatures[0] = signer.create_signature(&signer.create_random_secret());
+    assert!(!verify_or_proof(
+        group,
+        &signatures,
+        &proof,
+        &binding(),
+        b"context"
+    )?);
+
+    Ok(())
+}
+
+#[test]
+fn or_proof_with_shifted_challenges_is_rejected() -> TestResult<()> {
+    // Moving part of one branch's challenge to another keeps the sum, but breaks
+    // both branches.
+    let group = &*MODP_1024_160_GROUP;
+    let q = group.order();
+    let signer = Signer::from(group);
+    let (secrets, signatures) = members(&signer);
+    let mut proof = signer.create_or_proof(&secrets[0], &signatures, 0, &binding(), b"context")?;
+
+    let c0 = BigUint::from_bytes_be(&proof.branches[0].c);
+    let c1 = BigUint::from_bytes_be(&proof.branches[1].c);
+    proof.branches[0].c = ((c0 + 1u32) % q).to_bytes_be();
+    proof.branches[1].c = ((c1 + q - 1u32) % q).to_bytes_be();
+
+    assert!(!verify_or_proof(
+        group,
+        &signatures,
+        &proof,
+        &binding(),
+        b"context"
+    )?);
+
+    Ok(())
+}
+
+#[test]
+fn or_proof_without_the_secret_is_refused() {
+    let group = &*MODP_1024_160_GROUP;
+    let signer = Signer::from(group);
+    let (secrets, signatures) = members(&signer);
+
+    for (secret, index) in [(&secrets[0], 1), (&secrets[0], SIZE)] {
+        assert!(matches!(
+            signer.create_or_proof(secret, &signatures, index, &binding(), b"context"),
+            Err(Error::SecretNotInSignatureSet)
+        ));
+    }
+
+    assert!(matches!(
+        signer.create_or_proof(&secrets[0], &[], 0, &binding(), b"context"),
+        Err(Error::EmptySignatureSet)
+    ));
+}
+
+#[test]
+fn or_proof_with_missing_branches_is_incomplete() -> TestResult<()> {
+    let group = &*MODP_1024_160_GROUP;
+    let signer = Signer::from(group);
+    let (secrets, signatures) = members(&signer);
+    let mut proof = signer.create_or_proof(&secrets[0], &signatures, 0, &binding(), b"context")?;
+
+    proof.branches.pop();
+    assert!(matches!(
+        verify_or_proof(group, &signatures, &proof, &binding(), b"context"),
+        Err(Error::OrProofSizeMismatch)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn or_proof_over_mixed_groups_is_refused() {
+    let group = &*MODP_1024_160_GROUP;
+    let signer = Signer::from(group);
+    let (secrets, mut signatures) = members(&signer);
+
+    let ristretto = Signer::from(&*RISTRETTO255_GROUP);
+    signatures[3] = ristretto.create_signature(&ristretto.create_random_secret());
+
+    assert!(matches!(
+        signer.create_or_proof(&secrets[0], &signatures, 0, &binding(), b"context"),
+        Err(Error::GroupMismatch(_))
+    ));
+}
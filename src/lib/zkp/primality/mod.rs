@@ -0,0 +1,75 @@
+use lazy_static::lazy_static;
+use num_bigint::{BigUint, RandBigInt};
+
+#[cfg(test)]
+mod test;
+
+/// The number of Miller-Rabin rounds used when validating group parameters,
+/// giving a false-positive probability of at most 2^-128 for adversarial input.
+pub const MILLER_RABIN_ROUNDS: usize = 64;
+
+lazy_static! {
+    static ref SMALL_PRIMES: Vec<u32> = {
+        let mut primes = Vec::new();
+
+        for n in 2u32..1000 {
+            if primes.iter().all(|p| n % p != 0) {
+                primes.push(n);
+            }
+        }
+
+        primes
+    };
+}
+
+/// Checks whether `n` is probably prime, using trial division by small primes
+/// followed by `rounds` rounds of Miller-Rabin with random bases.
+pub fn is_probable_prime(n: &BigUint, rounds: usize) -> bool {
+    let zero = BigUint::from(0u32);
+    let one = BigUint::from(1u32);
+    let two = BigUint::from(2u32);
+
+    if *n < two {
+        return false;
+    }
+
+    for p in SMALL_PRIMES.iter() {
+        let p = BigUint::from(*p);
+
+        if *n == p {
+            return true;
+        } else if n % &p == zero {
+            return false;
+        }
+    }
+
+    // Write n - 1 as d * 2^r, with d odd.
+    let n_minus_one = n - &one;
+    let r = n_minus_one
+        .trailing_zeros()
+        .expect("n - 1 is nonzero for n > 2");
+    let d = &n_minus_one >> r;
+
+    let mut rng = rand::thread_rng();
+
+    'witness: for _ in 0..rounds {
+        let a = rng.gen_biguint_range(&two, &n_minus_one);
+        let mut x = a.modpow(&d, n);
+
+        if x == one || x == n_minus_one {
+            continue 'witness;
+        }
+
+        for _ in 1..r {
+            x = x.modpow(&two, n);
+
+            if x == n_minus_one {
+                continue 'witness;
+            }
+        }
+
+        return false;
+    }
+
+    true
+}
@@ -0,0 +1,34 @@
+use crate::zkp::primality::{is_probable_prime, MILLER_RABIN_ROUNDS};
+use num_bigint::BigUint;
+
+#[test]
+fn small_primes_are_prime() {
+    for n in [2u32, 3, 5, 11, 23, 997, 1009, 7919, 104729] {
+        assert!(is_probable_prime(&BigUint::from(n), MILLER_RABIN_ROUNDS));
+    }
+}
+
+#[test]
+fn small_composites_are_not_prime() {
+    for n in [0u32, 1, 4, 9, 1001, 1024, 7917, 104730] {
+        assert!(!is_probable_prime(&BigUint::from(n), MILLER_RABIN_ROUNDS));
+    }
+}
+
+#[test]
+fn carmichael_numbers_are_not_prime() {
+    // These fool the Fermat test for every coprime base, but not Miller-Rabin. The
+    // last one has no factors small enough to be caught by trial division.
+    for n in [561u64, 41041, 825265, 321197185, 9624742921] {
+        assert!(!is_probable_prime(&BigUint::from(n), MILLER_RABIN_ROUNDS));
+    }
+}
+
+#[test]
+fn large_mersenne_prime_is_prime() {
+    let m127 = (BigUint::from(1u32) << 127) - 1u32;
+    let m128 = (BigUint::from(1u32) << 128) - 1u32;
+
+    assert!(is_probable_prime(&m127, MILLER_RABIN_ROUNDS));
+    assert!(!is_probable_prime(&m128, MILLER_RABIN_ROUNDS));
+}
@@ -0,0 +1,265 @@
+use crate::{
+    grpc::auth::{Challenge, Commitment, GroupKind, Proof, ProtoGroup, Signature, Solution},
+    zkp::{
+        fiat_shamir::Binding,
+        preset_group,
+        ristretto::{RistrettoGroup, RISTRETTO255_GROUP},
+        verifier::{verify_proof, Verifier},
+        Error, Group, GroupId, PrimeOrderGroup,
+    },
+};
+use std::{collections::HashMap, sync::Arc};
+
+#[cfg(test)]
+mod test;
+
+/// A group that the server can accept sign-ups against, of any supported kind.
+#[derive(Clone, Debug)]
+pub enum RegisteredGroup {
+    ModP(Arc<Group>),
+    Ristretto255(Arc<RistrettoGroup>),
+}
+
+impl RegisteredGroup {
+    pub fn id(&self) -> &str {
+        match self {
+            Self::ModP(group) => group.id(),
+            Self::Ristretto255(group) => group.id(),
+        }
+    }
+
+    pub fn kind(&self) -> GroupKind {
+        match self {
+            Self::ModP(_) => GroupKind::Modp,
+            Self::Ristretto255(_) => GroupKind::Ristretto255,
+        }
+    }
+
+    pub fn security_bits(&self) -> u32 {
+        match self {
+            Self::ModP(group) => group.security_bits(),
+            Self::Ristretto255(group) => group.security_bits(),
+        }
+    }
+
+    pub fn to_proto(&self) -> ProtoGroup {
+        match self {
+            Self::ModP(group) => group.to_proto(),
+            Self::Ristretto255(group) => group.to_proto(),
+        }
+    }
+
+    /// Checks whether the given protobuf group describes exactly this group.
+    pub fn matches(&self, group: &ProtoGroup) -> bool {
+        match self {
+            Self::ModP(registered) => registered.matches(group),
+            Self::Ristretto255(registered) => registered.matches(group),
+        }
+    }
+
+    /// Checks that the signature's `y1` and `y2` are canonically encoded
+    /// elements of the group.
+    pub fn check_signature(&self, signature: &Signature) -> Result<(), Error> {
+        match self {
+            Self::ModP(group) => group
+                .decode(&signature.y1)
+                .and_then(|_| group.decode(&signature.y2))
+                .map(|_| ()),
+            Self::Ristretto255(group) => group
+                .decode(&signature.y1)
+                .and_then(|_| group.decode(&signature.y2))
+                .map(|_| ()),
+        }
+    }
+
+    /// Creates a verifier for the given signature and commitment in this group;
+    /// see [`Verifier`].
+    pub fn create_verifier(
+        &self,
+        signature: Signature,
+        commitment: Commitment,
+        binding: &Binding,
+    ) -> Result<RegisteredVerifier, Error> {
+        Ok(match self {
+            Self::ModP(group) => RegisteredVerifier::ModP(Verifier::try_from((
+                group.clone(),
+                signature,
+                commitment,
+                binding,
+            ))?),
+            Self::Ristretto255(group) => RegisteredVerifier::Ristretto255(Box::new(
+                Verifier::try_from((group.clone(), signature, commitment, binding))?,
+            )),
+        })
+    }
+
+    /// Verifies a non-interactive proof in this group; see [`verify_proof`].
+    pub fn verify_proof(
+        &self,
+        signature: &Signature,
+        proof: &Proof,
+        binding: &Binding,
+        context: &[u8],
+    ) -> Result<bool, Error> {
+        match self {
+            Self::ModP(group) => verify_proof(&**group, signature, proof, binding, context),
+            Self::Ristretto255(group) => verify_proof(&**group, signature, proof, binding, context),
+        }
+    }
+}
+
+impl From<Group> for RegisteredGroup {
+    fn from(group: Group) -> Self {
+        Self::ModP(Arc::new(group))
+    }
+}
+
+impl From<RistrettoGroup> for RegisteredGroup {
+    fn from(group: RistrettoGroup) -> Self {
+        Self::Ristretto255(Arc::new(group))
+    }
+}
+
+impl TryFrom<&ProtoGroup> for RegisteredGroup {
+    type Error = Error;
+
+    /// Validates a protobuf group of any supported kind.
+    fn try_from(group: &ProtoGroup) -> Result<Self, Self::Error> {
+        match group.kind() {
+            GroupKind::Modp => Group::try_from(group).map(Self::from),
+            GroupKind::Ristretto255 => RistrettoGroup::try_from(group).map(Self::from),
+        }
+    }
+}
+
+/// A verifier awaiting a solution, in whichever kind of group the signature was
+/// made against.
+#[derive(Debug)]
+pub enum RegisteredVerifier {
+    ModP(Verifier<Group>),
+    Ristretto255(Box<Verifier<RistrettoGroup>>),
+}
+
+impl RegisteredVerifier {
+    pub fn create_challenge(&self) -> Challenge {
+        match self {
+            Self::ModP(verifier) => verifier.create_challenge(),
+            Self::Ristretto255(verifier) => verifier.create_challenge(),
+        }
+    }
+
+    pub fn verify_solution(&self, solution: Solution) -> Result<bool, Error> {
+        match self {
+            Self::ModP(verifier) => verifier.verify_solution(solution),
+            Self::Ristretto255(verifier) => verifier.verify_solution(solution),
+        }
+    }
+}
+
+/// Looks up a built-in group by id: any of the mod-p presets, or the
+/// Ristretto255 group as `"ristretto255"`.
+pub fn preset(id: &str) -> Option<RegisteredGroup> {
+    if id == RISTRETTO255_GROUP.id() {
+        return Some(RISTRETTO255_GROUP.clone().into());
+    }
+
+    preset_group(id).map(|group| group.clone().into())
+}
+
+/// The set of groups a server is willing to accept sign-ups against, and the
+/// minimum security level that groups must offer to be used.
+#[derive(Debug, Default)]
+pub struct GroupRegistry {
+    groups: HashMap<GroupId, RegisteredGroup>,
+    min_security_bits: u32,
+}
+
+impl GroupRegistry {
+    pub fn new() -> Self {
+        Self {
+            groups: HashMap::new(),
+            min_security_bits: 0,
+        }
+    }
+
+    /// Requires groups to offer at least `bits` of security to be used for
+    /// sign-ups and logins. Weaker groups can still be registered, but aren't
+    /// offered to clients.
+    pub fn with_min_security_bits(mut self, bits: u32) -> Self {
+        self.min_security_bits = bits;
+        self
+    }
+
+    pub fn min_security_bits(&self) -> u32 {
+        self.min_security_bits
+    }
+
+    /// Checks that the group meets the minimum security level.
+    pub fn check_security(&self, group: &RegisteredGroup) -> Result<(), Error> {
+        let bits = group.security_bits();
+
+        if bits < self.min_security_bits {
+            return Err(Error::SecurityLevelTooLow {
+                group: group.id().to_string(),
+                bits,
+                required: self.min_security_bits,
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn register(&mut self, group: impl Into<RegisteredGroup>) -> Result<(), Error> {
+        let group = group.into();
+
+        if self.groups.contains_key(group.id()) {
+            return Err(Error::DuplicateGroup(group.id().to_string()));
+        }
+
+        self.groups.insert(group.id().to_string(), group);
+
+        Ok(())
+    }
+
+    pub fn get(&self, id: &str) -> Option<RegisteredGroup> {
+        self.groups.get(id).cloned()
+    }
+
+    /// Looks up the registered group referenced by the given protobuf group,
+    /// and makes sure that the client-supplied parameters are exactly the
+    /// registered ones, and that the group meets the minimum security level.
+    pub fn resolve(&self, group: &ProtoGroup) -> Result<RegisteredGroup, Error> {
+        let registered = self
+            .get(&group.id)
+            .ok_or_else(|| Error::GroupNotRegistered(group.id.clone()))?;
+
+        if !registered.matches(group) {
+            return Err(Error::GroupMismatch(group.id.clone()));
+        }
+
+        self.check_security(&registered)?;
+
+        Ok(registered)
+    }
+
+    /// Returns the registered groups that meet the minimum security level, for
+    /// offering to clients.
+    pub fn accepted_groups(&self) -> Vec<RegisteredGroup> {
+        self.groups()
+            .into_iter()
+            .filter(|group| self.check_security(group).is_ok())
+            .collect()
+    }
+
+    /// Returns the registered groups, ordered from weakest to strongest.
+    pub fn groups(&self) -> Vec<RegisteredGroup> {
+        let mut groups: Vec<RegisteredGroup> = self.groups.values().cloned().collect();
+        groups.sort_by(|a, b| {
+            a.security_bits()
+                .cmp(&b.security_bits())
+                .then_with(|| a.id().cmp(b.id()))
+        });
+
+        groups
+    }
+}
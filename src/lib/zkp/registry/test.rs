@@ -0,0 +1,205 @@
+use crate::{
+    grpc::auth::GroupKind,
+    zkp::{
+        fiat_shamir::Binding,
+        preset_group,
+        registry::{self, GroupRegistry, RegisteredGroup},
+        ristretto::RISTRETTO255_GROUP,
+        signer::Signer,
+        Error, Group, PrimeOrderGroup, MODP_1024_160_GROUP, MODP_2048_256_GROUP,
+    },
+};
+use num_bigint::BigUint;
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+fn registry_with(groups: &[&Group]) -> TestResult<GroupRegistry> {
+    let mut registry = GroupRegistry::new();
+
+    for group in groups {
+        registry.register((*group).clone())?;
+    }
+
+    Ok(registry)
+}
+
+#[test]
+fn preset_groups_are_found_by_id() {
+    assert!(preset_group("rfc5114-modp-1024-160").is_some());
+    assert!(preset_group("rfc5114-modp-4096-256").is_none());
+}
+
+#[test]
+fn ristretto255_is_a_preset() {
+    let group = registry::preset("ristretto255").unwrap();
+
+    assert_eq!(group.kind(), GroupKind::Ristretto255);
+    assert!(group.matches(&RISTRETTO255_GROUP.to_proto()));
+    assert_eq!(
+        registry::preset("rfc5114-modp-1024-160").map(|group| group.kind()),
+        Some(GroupKind::Modp)
+    );
+}
+
+#[test]
+fn registered_group_is_resolved() -> TestResult<()> {
+    let registry = registry_with(&[&MODP_1024_160_GROUP])?;
+    let group = registry.resolve(&MODP_1024_160_GROUP.to_proto())?;
+
+    assert_eq!(group.id(), MODP_1024_160_GROUP.id());
+
+    Ok(())
+}
+
+#[test]
+fn duplicate_group_is_rejected() -> TestResult<()> {
+    let mut registry = registry_with(&[&MODP_1024_160_GROUP])?;
+    let result = registry.register(MODP_1024_160_GROUP.clone());
+
+    assert!(matches!(result, Err(Error::DuplicateGroup(_))));
+
+    Ok(())
+}
+
+#[test]
+fn unregistered_group_is_rejected() -> TestResult<()> {
+    let registry = registry_with(&[&MODP_1024_160_GROUP])?;
+    let result = registry.resolve(&MODP_2048_256_GROUP.to_proto());
+
+    assert!(matches!(result, Err(Error::GroupNotRegistered(_))));
+
+    Ok(())
+}
+
+#[test]
+fn group_with_tampered_parameters_is_rejected() -> TestResult<()> {
+    let registry = registry_with(&[&MODP_1024_160_GROUP])?;
+
+    // Claim to be a registered group, but swap in a toy modulus.
+    let mut group = MODP_1024_160_GROUP.to_proto();
+    group.p = BigUint::from(23u32).to_bytes_be();
+
+    let result = registry.resolve(&group);
+
+    assert!(matches!(result, Err(Error::GroupMismatch(_))));
+
+    Ok(())
+}
+
+#[test]
+fn signature_group_matches_registered_group() -> TestResult<()> {
+    let registry = registry_with(&[&MODP_1024_160_GROUP])?;
+    let signer = Signer::from(&*MODP_1024_160_GROUP);
+    let secret = signer.create_random_secret();
+    let signature = signer.create_signature(&secret);
+
+    let group = signature.group.ok_or(Error::GroupNotSpecified)?;
+
+    assert!(registry.resolve(&group).is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn group_below_minimum_security_is_rejected() -> TestResult<()> {
+    let registry =
+        registry_with(&[&MODP_1024_160_GROUP, &MODP_2048_256_GROUP])?.with_min_security_bits(112);
+
+    assert!(matches!(
+        registry.resolve(&MODP_1024_160_GROUP.to_proto()),
+        Err(Error::SecurityLevelTooLow {
+            bits: 80,
+            required: 112,
+            ..
+        })
+    ));
+    registry.resolve(&MODP_2048_256_GROUP.to_proto())?;
+
+    let accepted: Vec<_> = registry
+        .accepted_groups()
+        .iter()
+        .map(|group| group.id().to_string())
+        .collect();
+    assert_eq!(accepted, vec![MODP_2048_256_GROUP.id()]);
+    assert_eq!(registry.groups().len(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn security_level_error_is_a_failed_precondition() {
+    let error = Error::SecurityLevelTooLow {
+        group: "rfc5114-modp-1024-160".to_string(),
+        bits: 80,
+        required: 112,
+    };
+    let status = tonic::Status::from(error);
+
+    assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+    assert!(status.message().contains("minimum of 112 bits"));
+}
+
+#[test]
+fn ristretto255_group_is_registered_and_resolved() -> TestResult<()> {
+    let mut registry = registry_with(&[&MODP_2048_256_GROUP])?.with_min_security_bits(112);
+    registry.register(RISTRETTO255_GROUP.clone())?;
+
+    let group = registry.resolve(&RISTRETTO255_GROUP.to_proto())?;
+    assert_eq!(group.kind(), GroupKind::Ristretto255);
+
+    // A mod-p group can't claim the Ristretto255 id.
+    let mut claimed = MODP_2048_256_GROUP.to_proto();
+    claimed.id = RISTRETTO255_GROUP.id().to_string();
+    assert!(matches!(
+        registry.resolve(&claimed),
+        Err(Error::GroupMismatch(_))
+    ));
+
+    let accepted: Vec<_> = registry
+        .accepted_groups()
+        .iter()
+        .map(|group| group.id().to_string())
+        .collect();
+    assert_eq!(
+        accepted,
+        vec![MODP_2048_256_GROUP.id(), RISTRETTO255_GROUP.id()]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn proofs_verify_in_registered_groups_of_either_kind() -> TestResult<()> {
+    let mut registry = registry_with(&[&MODP_1024_160_GROUP])?;
+    registry.register(RISTRETTO255_GROUP.clone())?;
+
+    for registered in registry.groups() {
+        match RegisteredGroup::try_from(&registered.to_proto())? {
+            RegisteredGroup::ModP(group) => authenticate(&registered, &*group)?,
+            RegisteredGroup::Ristretto255(group) => authenticate(&registered, &*group)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Signs up in the group, then logs in both interactively and non-interactively
+/// through the registered group.
+fn authenticate<G: PrimeOrderGroup>(registered: &RegisteredGroup, group: &G) -> TestResult<()> {
+    let binding = Binding::from(("server", "user"));
+    let signer = Signer::from(group);
+    let secret = signer.create_random_secret();
+    let signature = signer.create_signature(&secret);
+
+    registered.check_signature(&signature)?;
+
+    let proof = signer.create_proof(&secret, &binding, b"context");
+    assert!(registered.verify_proof(&signature, &proof, &binding, b"context")?);
+
+    let (commitment, prover) = signer.create_commitment();
+    let verifier = registered.create_verifier(signature, commitment, &binding)?;
+    let solution = prover.create_solution(&secret, &binding, verifier.create_challenge())?;
+    assert!(verifier.verify_solution(solution)?);
+
+    Ok(())
+}
@@ -0,0 +1,202 @@
+use crate::{
+    grpc::auth::{GroupKind, ProtoGroup},
+    zkp::{constant_time::Montgomery, secret::reduce_mod_q, Error, GroupId, PrimeOrderGroup},
+};
+use curve25519_dalek::{
+    constants::RISTRETTO_BASEPOINT_POINT,
+    ristretto::{CompressedRistretto, RistrettoPoint},
+    scalar::Scalar,
+    traits::{Identity, VartimeMultiscalarMul},
+};
+use lazy_static::lazy_static;
+use num_bigint::BigUint;
+use sha2::Sha512;
+use zeroize::Zeroizing;
+
+#[cfg(test)]
+mod test;
+
+/// The input hashed to derive `beta`, so that nobody knows `log_alpha(beta)`.
+const BETA_SEED: &str = "zkp-chaum-pedersen/ristretto255/beta";
+
+lazy_static! {
+    pub static ref RISTRETTO255_GROUP: RistrettoGroup = RistrettoGroup::new("ristretto255");
+}
+
+/// The Ristretto255 prime-order group, built on Curve25519. Elements are
+/// sent over the wire as 32-byte compressed points.
+#[derive(Clone, Debug)]
+pub struct RistrettoGroup {
+    id: GroupId,
+    q: BigUint,
+    montgomery_q: Montgomery,
+    alpha: RistrettoPoint,
+    beta: RistrettoPoint,
+}
+
+impl RistrettoGroup {
+    fn new(id: &str) -> Self {
+        // The largest scalar is q - 1.
+        let q = BigUint::from_bytes_le((-Scalar::ONE).as_bytes()) + 1u32;
+
+        Self {
+            id: id.to_string(),
+            montgomery_q: Montgomery::new(&q),
+            q,
+            alpha: RISTRETTO_BASEPOINT_POINT,
+            beta: RistrettoPoint::hash_from_bytes::<Sha512>(BETA_SEED.as_bytes()),
+        }
+    }
+
+    fn to_scalar(&self, value: &BigUint) -> Scalar {
+        let mut bytes = [0u8; 32];
+        let le_bytes = (value % &self.q).to_bytes_le();
+        bytes[..le_bytes.len()].copy_from_slice(&le_bytes);
+
+        Scalar::from_bytes_mod_order(bytes)
+    }
+}
+
+impl PrimeOrderGroup for RistrettoGroup {
+    type Element = RistrettoPoint;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn order(&self) -> &BigUint {
+        &self.q
+    }
+
+    fn montgomery_q(&self) -> &Montgomery {
+        &self.montgomery_q
+    }
+
+    /// Pollard's rho in a group of order about `2^252` takes about `2^126` steps,
+    /// which is conventionally rated as 128-bit security.
+    fn security_bits(&self) -> u32 {
+        128
+    }
+
+    fn alpha(&self) -> &RistrettoPoint {
+        &self.alpha
+    }
+
+    fn beta(&self) -> &RistrettoPoint {
+        &self.beta
+    }
+
+    fn hash_to_element(&self, input: &[u8]) -> RistrettoPoint {
+        RistrettoPoint::hash_from_bytes::<Sha512>(input)
+    }
+
+    fn identity(&self) -> RistrettoPoint {
+        RistrettoPoint::identity()
+    }
+
+    fn exp(&self, base: &RistrettoPoint, exponent: &BigUint) -> RistrettoPoint {
+        base * self.to_scalar(exponent)
+    }
+
+    fn exp_secret(&self, base: &RistrettoPoint, exponent: &BigUint) -> RistrettoPoint {
+        // Reduce the exponent in constant time rather than with the
+        // (variable-time) reduction in `to_scalar`; dalek's scalar multiplication
+        // is itself constant-time.
+        let exponent = reduce_mod_q(self, exponent);
+        let mut bytes = [0u8; 32];
+        let le_bytes = Zeroizing::new(exponent.expose().to_bytes_le());
+        bytes[..le_bytes.len()].copy_from_slice(&le_bytes);
+
+        base * Scalar::from_bytes_mod_order(bytes)
+    }
+
+    fn mul(&self, a: &RistrettoPoint, b: &RistrettoPoint) -> RistrettoPoint {
+        a + b
+    }
+
+    fn multi_exp(&self, bases: &[RistrettoPoint], exponents: &[BigUint]) -> RistrettoPoint {
+        RistrettoPoint::vartime_multiscalar_mul(
+            exponents.iter().map(|exponent| self.to_scalar(exponent)),
+            bases,
+        )
+    }
+
+    fn double_exp(
+        &self,
+        g: &RistrettoPoint,
+        a: &BigUint,
+        h: &RistrettoPoint,
+        b: &BigUint,
+    ) -> RistrettoPoint {
+        // Dalek has a faster routine for when one of the bases is the basepoint.
+        if *g == self.alpha {
+            RistrettoPoint::vartime_double_scalar_mul_basepoint(
+                &self.to_scalar(b),
+                h,
+                &self.to_scalar(a),
+            )
+        } else {
+            RistrettoPoint::vartime_multiscalar_mul([self.to_scalar(a), self.to_scalar(b)], [g, h])
+        }
+    }
+
+    fn check_element(&self, _element: &RistrettoPoint) -> Result<(), Error> {
+        // Every Ristretto point is in the prime-order group.
+        Ok(())
+    }
+
+    fn encode(&self, element: &RistrettoPoint) -> Vec<u8> {
+        element.compress().to_bytes().to_vec()
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<RistrettoPoint, Error> {
+        CompressedRistretto::from_slice(bytes)
+            .ok()
+            .and_then(|point| point.decompress())
+            .ok_or(Error::InvalidElement)
+    }
+
+    fn to_proto(&self) -> ProtoGroup {
+        ProtoGroup {
+            id: self.id.clone(),
+            kind: GroupKind::Ristretto255.into(),
+            p: Vec::new(),
+            q: self.q.to_bytes_be(),
+            alpha: self.encode(&self.alpha),
+            beta: self.encode(&self.beta),
+            beta_seed: BETA_SEED.to_string(),
+        }
+    }
+}
+
+impl TryFrom<&ProtoGroup> for RistrettoGroup {
+    type Error = Error;
+
+    /// Accepts a protobuf group only if it describes exactly the Ristretto255
+    /// group with the standard basepoint and the hash-derived `beta`.
+    fn try_from(group: &ProtoGroup) -> Result<Self, Self::Error> {
+        if group.kind() != GroupKind::Ristretto255 {
+            return Err(Error::UnsupportedGroupKind);
+        }
+
+        let ristretto = Self::new(&group.id);
+
+        if ristretto.decode(&group.alpha)? != ristretto.alpha {
+            return Err(Error::InvalidAlpha);
+        }
+
+        if ristretto.decode(&group.beta)? != ristretto.beta {
+            return Err(Error::InvalidBeta);
+        }
+
+        if group.beta_seed != BETA_SEED {
+            return Err(Error::BetaNotFromSeed);
+        }
+
+        if ristretto.matches(group) {
+            Ok(ristretto)
+        } else {
+            Err(Error::GroupMismatch(group.id.clone()))
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use crate::zkp::{
+    ristretto::{RistrettoGroup, RISTRETTO255_GROUP},
+    Error, PrimeOrderGroup,
+};
+use num_bigint::BigUint;
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+#[test]
+fn group_order_is_the_prime_subgroup_order() {
+    // q = 2^252 + 27742317777372353535851937790883648493
+    let q = (BigUint::from(1u32) << 252)
+        + BigUint::parse_bytes(b"27742317777372353535851937790883648493", 10).unwrap();
+
+    assert_eq!(*RISTRETTO255_GROUP.order(), q);
+}
+
+#[test]
+fn alpha_and_beta_have_order_q() {
+    let group = &*RISTRETTO255_GROUP;
+    let identity = group.exp(group.alpha(), &BigUint::from(0u32));
+
+    assert_eq!(group.exp(group.alpha(), group.order()), identity);
+    assert_eq!(group.exp(group.beta(), group.order()), identity);
+    assert_ne!(group.alpha(), group.beta());
+}
+
+#[test]
+fn elements_round_trip_as_compressed_points() -> TestResult<()> {
+    let group = &*RISTRETTO255_GROUP;
+    let element = group.exp(group.beta(), &group.random_scalar());
+    let bytes = group.encode(&element);
+
+    assert_eq!(bytes.len(), 32);
+    assert_eq!(group.decode(&bytes)?, element);
+
+    Ok(())
+}
+
+#[test]
+fn invalid_encodings_are_rejected() {
+    let group = &*RISTRETTO255_GROUP;
+
+    // Wrong length.
+    assert!(matches!(
+        group.decode(&[0u8; 31]),
+        Err(Error::InvalidElement)
+    ));
+
+    // Not a valid Ristretto encoding (the encoded field element must be even).
+    let mut bytes = [0u8; 32];
+    bytes[0] = 1;
+
+    assert!(matches!(group.decode(&bytes), Err(Error::InvalidElement)));
+}
+
+#[test]
+fn proto_group_round_trips() -> TestResult<()> {
+    let group = RistrettoGroup::try_from(&RISTRETTO255_GROUP.to_proto())?;
+
+    assert!(RISTRETTO255_GROUP.matches(&group.to_proto()));
+
+    Ok(())
+}
+
+#[test]
+fn proto_group_with_substituted_beta_is_rejected() {
+    let group = &*RISTRETTO255_GROUP;
+    let mut proto = group.to_proto();
+    proto.beta = group.encode(&group.exp(group.alpha(), &BigUint::from(2u32)));
+
+    assert!(matches!(
+        RistrettoGroup::try_from(&proto),
+        Err(Error::InvalidBeta)
+    ));
+}
+
+#[test]
+fn proto_group_with_another_beta_seed_is_rejected() {
+    let mut proto = RISTRETTO255_GROUP.to_proto();
+    proto.beta_seed = "another seed".to_string();
+
+    assert!(matches!(
+        RistrettoGroup::try_from(&proto),
+        Err(Error::BetaNotFromSeed)
+    ));
+}
+
+#[test]
+fn multi_exp_matches_product_of_exps() {
+    let group = &*RISTRETTO255_GROUP;
+    let bases = [
+        *group.alpha(),
+        *group.beta(),
+        group.exp(group.beta(), &group.random_scalar()),
+    ];
+    let exponents = [
+        BigUint::from(0u32),
+        group.order() - 1u32,
+        group.random_scalar(),
+    ];
+
+    let product = bases
+        .iter()
+        .zip(exponents.iter())
+        .fold(group.identity(), |acc, (base, exponent)| {
+            group.mul(&acc, &group.exp(base, exponent))
+        });
+
+    assert_eq!(group.multi_exp(&bases, &exponents), product);
+}
@@ -0,0 +1,100 @@
+use crate::zkp::{Error, PrimeOrderGroup};
+use num_bigint::BigUint;
+use std::hint::black_box;
+use zeroize::Zeroizing;
+
+#[cfg(test)]
+mod test;
+
+/// A secret scalar in `[0, q)`, i.e. a user's secret or a commitment nonce.
+///
+/// It can't be printed or cloned, and its digits are overwritten when it's
+/// dropped. `BigUint` doesn't expose its buffer, so the digits are overwritten
+/// through it; any spare capacity left over from arithmetic on the value can't
+/// be reached, so secrets are best built fresh (e.g. from bytes) as they are
+/// here.
+///
+/// ```compile_fail
+/// use lib::zkp::{signer::Signer, MODP_1024_160_GROUP};
+///
+/// let secret = Signer::from(&*MODP_1024_160_GROUP).create_random_secret();
+///
+/// println!("{:?}", secret); // Error: `Secret` doesn't implement `Debug`.
+/// ```
+pub struct Secret(BigUint);
+
+impl Secret {
+    /// Wraps a scalar that's already known to be in `[0, q)`, e.g. one drawn or
+    /// reduced mod `q`. Anything else would be too wide for the constant-time
+    /// arithmetic on secrets.
+    pub(crate) fn new(value: BigUint) -> Self {
+        Self(value)
+    }
+
+    /// Exposes the secret value, for arithmetic.
+    pub fn expose(&self) -> &BigUint {
+        &self.0
+    }
+}
+
+impl<G: PrimeOrderGroup> TryFrom<(&G, BigUint)> for Secret {
+    type Error = Error;
+
+    /// Accepts a secret for the given group only if it's in `[0, q)`.
+    fn try_from((group, value): (&G, BigUint)) -> Result<Self, Self::Error> {
+        // Wrap it first, so that it's wiped even when it's refused.
+        let secret = Self(value);
+
+        if secret.0 >= *group.order() {
+            return Err(Error::ScalarOutOfRange);
+        }
+
+        Ok(secret)
+    }
+}
+
+/// Reduces `value` mod `q` in constant time. A `Secret` isn't tied to a group,
+/// so one made for a group of larger order may be used with this one, and the
+/// constant-time arithmetic needs it in `[0, q)`.
+pub(crate) fn reduce_mod_q<G: PrimeOrderGroup>(group: &G, value: &BigUint) -> Secret {
+    let bytes = Zeroizing::new(value.to_bytes_be());
+
+    Secret::new(group.montgomery_q().reduce(&bytes))
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        wipe(&mut self.0);
+    }
+}
+
+/// Overwrites the digits of `value` in place, leaving a power of two of the
+/// same width. The top digit is set, so that the value isn't normalized (and its
+/// buffer shrunk) before the writes are observed.
+fn wipe(value: &mut BigUint) {
+    let digits = value.bits().div_ceil(32) as usize;
+
+    if digits > 0 {
+        let mut overwrite = vec![0u32; digits];
+        overwrite[digits - 1] = 1;
+
+        value.assign_from_slice(&overwrite);
+        black_box(&*value);
+    }
+}
+
+/// A password, which can't be printed or cloned, and is zeroized on drop.
+pub struct Password(Zeroizing<String>);
+
+impl Password {
+    /// Exposes the password, for key derivation.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for Password {
+    fn from(password: String) -> Self {
+        Self(Zeroizing::new(password))
+    }
+}
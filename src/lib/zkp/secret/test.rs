@@ -0,0 +1,41 @@
+use crate::zkp::{
+    secret::{wipe, Password, Secret},
+    Error, PrimeOrderGroup, MODP_0005_004_GROUP,
+};
+use num_bigint::BigUint;
+
+#[test]
+fn wiping_overwrites_every_digit() {
+    for bits in [1u32, 31, 32, 33, 64, 65, 160, 2048] {
+        let mut value = (BigUint::from(1u32) << bits) - 1u32;
+        wipe(&mut value);
+
+        // Only the lowest bit of the top 32-bit digit is left set.
+        assert_eq!(value, BigUint::from(1u32) << ((bits - 1) / 32 * 32));
+    }
+
+    let mut zero = BigUint::from(0u32);
+    wipe(&mut zero);
+
+    assert_eq!(zero, BigUint::from(0u32));
+}
+
+#[test]
+fn secrets_and_passwords_expose_their_values() {
+    let secret = Secret::new(BigUint::from(42u32));
+    let password = Password::from(String::from("password123"));
+
+    assert_eq!(*secret.expose(), BigUint::from(42u32));
+    assert_eq!(password.expose(), "password123");
+}
+
+#[test]
+fn secrets_must_be_below_q() {
+    let group = &*MODP_0005_004_GROUP;
+
+    assert!(Secret::try_from((group, BigUint::from(10u32))).is_ok());
+    assert!(matches!(
+        Secret::try_from((group, group.order().clone())),
+        Err(Error::ScalarOutOfRange)
+    ));
+}
@@ -1,75 +1,296 @@
 use crate::{
-    grpc::auth::{Challenge, Commitment, Signature, Solution},
-    zkp::Group,
+    grpc::auth::{Challenge, Commitment, DleqProof, OrProof, Proof, Signature, Solution, VrfProof},
+    zkp::{
+        dleq,
+        fiat_shamir::{derive_challenge, start_transcript, Binding},
+        kdf::{derive_secret, KdfParams},
+        nonce::{derive_nonce, fresh_entropy, NonceMode},
+        or_proof,
+        secret::{reduce_mod_q, Password, Secret},
+        vrf, Error, Group, PrimeOrderGroup,
+    },
 };
-use num_bigint::{BigUint, RandBigInt};
+use num_bigint::BigUint;
 
-pub struct Signer {
-    group: &'static Group,
-    k: BigUint,
+pub struct Signer<'a, G: PrimeOrderGroup = Group> {
+    group: &'a G,
+    nonce_mode: NonceMode,
 }
 
-impl Signer {
-    pub fn create_random_secret(&self) -> BigUint {
-        rand::thread_rng().gen_biguint_below(&self.group.q)
+/// The prover's half of an interactive session: the nonce `k` behind a
+/// commitment, waiting for the verifier's challenge.
+///
+/// Solving a challenge consumes the state, and it can't be cloned, so each
+/// nonce answers exactly one challenge. (Answering two challenges with the same
+/// `k` would reveal the secret.)
+///
+/// ```compile_fail
+/// use lib::zkp::{signer::Signer, MODP_1024_160_GROUP};
+/// # use lib::{grpc::auth::Challenge, zkp::fiat_shamir::Binding};
+/// # let (c1, c2) = (Challenge::default(), Challenge::default());
+/// # let binding = Binding::from(("server", "alice"));
+///
+/// let signer = Signer::from(&*MODP_1024_160_GROUP);
+/// let secret = signer.create_random_secret();
+/// let (_commitment, prover) = signer.create_commitment();
+///
+/// let s1 = prover.create_solution(&secret, &binding, c1);
+/// let s2 = prover.create_solution(&secret, &binding, c2); // Error: `prover` was moved.
+/// ```
+pub struct ProverState<'a, G: PrimeOrderGroup = Group> {
+    group: &'a G,
+    k: Secret,
+    r1: G::Element,
+    r2: G::Element,
+}
+
+impl<'a, G: PrimeOrderGroup> Signer<'a, G> {
+    pub fn create_random_secret(&self) -> Secret {
+        Secret::new(self.group.random_scalar())
     }
 
-    pub fn create_secret_from_password(&self, password: String) -> BigUint {
-        // This should be salted before using in an actual production environment.
-        BigUint::from_bytes_be(sha256::digest(password).as_bytes())
-            .modpow(&BigUint::from(1u32), &self.group.q)
+    /// Derives the secret from a password with Argon2id, using the salt and cost
+    /// that the server issued for the user.
+    pub fn create_secret_from_password(
+        &self,
+        password: &Password,
+        kdf: &KdfParams,
+    ) -> Result<Secret, Error> {
+        derive_secret(self.group, password.expose().as_bytes(), kdf)
     }
 
-    pub fn create_signature(&self, secret: &BigUint) -> Signature {
+    pub fn create_signature(&self, secret: &Secret) -> Signature {
         Signature {
             group: Some(self.group.to_proto()),
-            y1: self.group.alpha.modpow(secret, &self.group.p).to_bytes_be(),
-            y2: self.group.beta.modpow(secret, &self.group.p).to_bytes_be(),
+            y1: self
+                .group
+                .encode(&self.group.exp_secret(self.group.alpha(), secret.expose())),
+            y2: self
+                .group
+                .encode(&self.group.exp_secret(self.group.beta(), secret.expose())),
+            kdf: None,
         }
     }
 
-    pub fn create_commitment(&self) -> Commitment {
-        Commitment {
-            r1: self
-                .group
-                .alpha
-                .modpow(&self.k, &self.group.p)
-                .to_bytes_be(),
-            r2: self.group.beta.modpow(&self.k, &self.group.p).to_bytes_be(),
+    /// Commits to a fresh nonce `k`, returning the commitment for the verifier
+    /// along with the one-shot state needed to answer its challenge.
+    ///
+    /// The nonce is always drawn from the RNG, whatever the nonce mode: the
+    /// commitment is made before there's any context to hedge with, so a hedged
+    /// `k` would repeat across sessions whenever the RNG failed, and answering
+    /// two challenges with the same `k` reveals the secret.
+    pub fn create_commitment(&self) -> (Commitment, ProverState<'a, G>) {
+        let group = self.group;
+        let (r1, r2, prover) = self.commit_to_nonce(
+            Secret::new(group.random_scalar()),
+            (group.alpha(), group.beta()),
+        );
+
+        let commitment = Commitment {
+            r1: self.group.encode(&r1),
+            r2: self.group.encode(&r2),
+        };
+
+        (commitment, prover)
+    }
+
+    /// Creates a non-interactive proof of knowledge of the secret, bound to the
+    /// given server, account and context. The challenge is derived from a hash
+    /// of the transcript, so no round trips to the verifier are needed.
+    ///
+    /// A hedged nonce is derived from the whole statement (the binding, `y1`,
+    /// `y2` and the context), so it's never reused across statements.
+    pub fn create_proof(&self, secret: &Secret, binding: &Binding, context: &[u8]) -> Proof {
+        let group = self.group;
+        let y1 = group.exp_secret(group.alpha(), secret.expose());
+        let y2 = group.exp_secret(group.beta(), secret.expose());
+
+        let mut transcript = start_transcript(group, binding, (&y1, &y2), context);
+        let (r1, r2, prover) = self.commit(
+            secret,
+            &transcript.clone().challenge_bytes(b"nonce-context"),
+        );
+
+        transcript.append_element(group, b"r1", &r1);
+        transcript.append_element(group, b"r2", &r2);
+        let c = transcript.challenge_scalar(group, b"c");
+
+        Proof {
+            commitment: Some(Commitment {
+                r1: group.encode(&r1),
+                r2: group.encode(&r2),
+            }),
+            solution: Some(Solution {
+                s: prover.solve(secret, &c).to_bytes_be(),
+            }),
         }
     }
 
-    /// Finds a solution to the given challenge, i.e. solves for `s` where
-    /// `s = k - (c * x) mod q`.
-    pub fn create_solution(&self, secret: &BigUint, challenge: Challenge) -> Solution {
-        let cx = BigUint::from_bytes_be(&challenge.c) * secret;
-
-        let s = if self.k >= cx {
-            (&self.k - cx).modpow(&BigUint::from(1u32), &self.group.q)
-        } else {
-            &self.group.q - (cx - &self.k).modpow(&BigUint::from(1u32), &self.group.q)
+    /// Creates a non-interactive OR-proof that the secret is behind one of the
+    /// given signatures (the one at `index`), without revealing which, bound to
+    /// the given server, account and context.
+    pub fn create_or_proof(
+        &self,
+        secret: &Secret,
+        signatures: &[Signature],
+        index: usize,
+        binding: &Binding,
+        context: &[u8],
+    ) -> Result<OrProof, Error> {
+        or_proof::prove(
+            self.group,
+            secret,
+            signatures,
+            index,
+            binding,
+            context,
+            |nonce_context| self.commit(secret, nonce_context),
+            |nonce_context| self.draw_nonce(secret, nonce_context),
+        )
+    }
+
+    /// Creates a non-interactive proof that `u = g^x` and `v = h^x` share the
+    /// secret `x`, for arbitrary bases `g` and `h`, bound to the given context.
+    pub fn create_dleq_proof(
+        &self,
+        secret: &Secret,
+        bases: (&G::Element, &G::Element),
+        context: &[u8],
+    ) -> Result<DleqProof, Error> {
+        dleq::prove(self.group, secret, bases, context, |nonce_context| {
+            self.commit_to_bases(secret, bases, nonce_context)
+        })
+    }
+
+    /// Evaluates the verifiable random function on the input, returning the
+    /// output along with a proof that it was computed with the secret behind
+    /// this signer's signature.
+    pub fn prove_vrf(&self, secret: &Secret, input: &[u8]) -> Result<(Vec<u8>, VrfProof), Error> {
+        vrf::prove(self.group, secret, input, |bases, context| {
+            self.create_dleq_proof(secret, bases, context)
+        })
+    }
+
+    fn commit(
+        &self,
+        secret: &Secret,
+        context: &[u8],
+    ) -> (G::Element, G::Element, ProverState<'a, G>) {
+        self.commit_to_bases(secret, (self.group.alpha(), self.group.beta()), context)
+    }
+
+    /// Commits to a nonce `k` over the bases `g` and `h`, i.e. computes
+    /// `r1 = g^k` and `r2 = h^k`.
+    fn commit_to_bases(
+        &self,
+        secret: &Secret,
+        (g, h): (&G::Element, &G::Element),
+        context: &[u8],
+    ) -> (G::Element, G::Element, ProverState<'a, G>) {
+        self.commit_to_nonce(self.draw_nonce(secret, context), (g, h))
+    }
+
+    /// Draws a nonce according to the signer's nonce mode: from the RNG, or
+    /// hedged with the secret and the given context.
+    fn draw_nonce(&self, secret: &Secret, context: &[u8]) -> Secret {
+        match self.nonce_mode {
+            NonceMode::Random => Secret::new(self.group.random_scalar()),
+            NonceMode::Hedged => derive_nonce(self.group, secret, context, &fresh_entropy()),
+        }
+    }
+
+    /// Computes `r1 = g^k` and `r2 = h^k` for the given nonce `k`.
+    fn commit_to_nonce(
+        &self,
+        k: Secret,
+        (g, h): (&G::Element, &G::Element),
+    ) -> (G::Element, G::Element, ProverState<'a, G>) {
+        let group = self.group;
+        let r1 = group.exp_secret(g, k.expose());
+        let r2 = group.exp_secret(h, k.expose());
+
+        let prover = ProverState {
+            group,
+            k,
+            r1: r1.clone(),
+            r2: r2.clone(),
         };
 
-        Solution { s: s.to_bytes_be() }
+        (r1, r2, prover)
+    }
+}
+
+impl<'a, G: PrimeOrderGroup> ProverState<'a, G> {
+    /// Finds a solution to the given challenge, i.e. solves for `s` where
+    /// `s = k - (c * x) mod q`. The challenge must be a canonically encoded
+    /// scalar in `[0, q)`, derived from this transcript and its nonce with the
+    /// given binding; otherwise it may have been relayed from another server or
+    /// made for another account, and is refused.
+    pub fn create_solution(
+        self,
+        secret: &Secret,
+        binding: &Binding,
+        challenge: Challenge,
+    ) -> Result<Solution, Error> {
+        let group = self.group;
+        let c = group.decode_scalar(&challenge.c)?;
+
+        let y1 = group.exp_secret(group.alpha(), secret.expose());
+        let y2 = group.exp_secret(group.beta(), secret.expose());
+        let expected = derive_challenge(
+            group,
+            binding,
+            (&y1, &y2),
+            (&self.r1, &self.r2),
+            &challenge.nonce,
+        );
+
+        if c != expected {
+            return Err(Error::ChallengeNotBound);
+        }
+
+        Ok(Solution {
+            s: self.solve(secret, &c).to_bytes_be(),
+        })
+    }
+
+    /// Computes `s = k - c * x mod q` in constant time, for `c` and the secret `x`
+    /// in `[0, q)`.
+    pub(super) fn solve(self, secret: &Secret, c: &BigUint) -> BigUint {
+        let x = reduce_mod_q(self.group, secret.expose());
+
+        self.group
+            .montgomery_q()
+            .sub_mul_mod(self.k.expose(), c, x.expose())
     }
 
     #[cfg(test)]
     /// Create a provably invalid solution to the challenge (for testing purposes).
-    pub fn create_invalid_solution(&self, secret: &BigUint, challenge: Challenge) -> Solution {
-        let s_valid = BigUint::from_bytes_be(&self.create_solution(secret, challenge).s);
+    pub fn create_invalid_solution(
+        self,
+        secret: &Secret,
+        binding: &Binding,
+        challenge: Challenge,
+    ) -> Result<Solution, Error> {
+        let q = self.group.order();
+        let s_valid = BigUint::from_bytes_be(&self.create_solution(secret, binding, challenge)?.s);
         let offset = BigUint::from(1u32);
-        let s_invalid = (s_valid + offset).modpow(&BigUint::from(1u32), &self.group.q);
+        let s_invalid = (s_valid + offset) % q;
 
-        Solution {
+        Ok(Solution {
             s: s_invalid.to_bytes_be(),
-        }
+        })
     }
 }
 
-impl From<&'static Group> for Signer {
-    fn from(group: &'static Group) -> Self {
-        let k = rand::thread_rng().gen_biguint_below(&group.q);
+impl<'a, G: PrimeOrderGroup> From<&'a G> for Signer<'a, G> {
+    fn from(group: &'a G) -> Self {
+        Self::from((group, NonceMode::default()))
+    }
+}
 
-        Self { group, k }
+impl<'a, G: PrimeOrderGroup> From<(&'a G, NonceMode)> for Signer<'a, G> {
+    fn from((group, nonce_mode): (&'a G, NonceMode)) -> Self {
+        Self { group, nonce_mode }
     }
 }
@@ -1,46 +1,115 @@
-use crate::zkp::{
-    signer::Signer, verifier::Verifier, Group, MODP_0005_004_GROUP, MODP_1024_160_GROUP,
-    MODP_2048_224_GROUP, MODP_2048_256_GROUP,
+use crate::{
+    grpc::auth::{Challenge, Solution},
+    zkp::{
+        fiat_shamir::Binding,
+        nonce::NonceMode,
+        preset_group,
+        primality::is_probable_prime,
+        ristretto::RISTRETTO255_GROUP,
+        secret::Secret,
+        signer::Signer,
+        verifier::{verify_proof, Verifier},
+        Error, Group, PrimeOrderGroup, FFDHE_2048_GROUP, FFDHE_3072_GROUP, FFDHE_4096_GROUP,
+        FFDHE_6144_GROUP, FFDHE_8192_GROUP, INSECURE_DEMO_GROUP_IDS, MODP_0005_004_GROUP,
+        MODP_1024_160_GROUP, MODP_1536_1535_GROUP, MODP_2048_2047_GROUP, MODP_2048_224_GROUP,
+        MODP_2048_256_GROUP, MODP_3072_3071_GROUP, MODP_4096_4095_GROUP, MODP_6144_6143_GROUP,
+        MODP_8192_8191_GROUP,
+    },
 };
+use num_bigint::BigUint;
+use std::sync::Arc;
 
 type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
 
-fn test_valid_solution_for_group(group: &'static Group) -> TestResult<()> {
+/// The server and account that tests bind their proofs to.
+pub(super) fn binding() -> Binding {
+    Binding::from(("test-server", "alice"))
+}
+
+/// Parses a big-endian hex string, as test vectors are written.
+pub(super) fn hex_int(hex_str: &str) -> BigUint {
+    BigUint::parse_bytes(hex_str.as_bytes(), 16).unwrap()
+}
+
+fn test_valid_solution_for_group<G: PrimeOrderGroup + Clone>(group: &G) -> TestResult<()> {
     // Set up the signer and get a commitment.
-    let signer = Signer::try_from(group)?;
+    let signer = Signer::from(group);
     let secret = signer.create_random_secret();
     let signature = signer.create_signature(&secret);
-    let commitment = signer.create_commitment();
+    let (commitment, prover) = signer.create_commitment();
 
     // Set up the verifier and get a challenge.
-    let verifier = Verifier::try_from((signature, commitment))?;
+    let verifier =
+        Verifier::try_from((Arc::new(group.clone()), signature, commitment, &binding()))?;
     let challenge = verifier.create_challenge();
 
     // Create a valid solution to the challenge.
-    let solution = signer.create_solution(&secret, challenge);
+    let solution = prover.create_solution(&secret, &binding(), challenge)?;
 
     // Test to make sure that the solution passes.
-    assert!(verifier.verify_solution(solution));
+    assert!(verifier.verify_solution(solution)?);
 
     Ok(())
 }
 
-fn test_invalid_solution_for_group(group: &'static Group) -> TestResult<()> {
+fn test_invalid_solution_for_group<G: PrimeOrderGroup + Clone>(group: &G) -> TestResult<()> {
     // Set up the signer and get a commitment.
-    let signer = Signer::try_from(group)?;
+    let signer = Signer::from(group);
     let secret = signer.create_random_secret();
     let signature = signer.create_signature(&secret);
-    let commitment = signer.create_commitment();
+    let (commitment, prover) = signer.create_commitment();
 
     // Set up the verifier and get a challenge.
-    let verifier = Verifier::try_from((signature, commitment))?;
+    let verifier =
+        Verifier::try_from((Arc::new(group.clone()), signature, commitment, &binding()))?;
     let challenge = verifier.create_challenge();
 
     // Create an invalid solution to the challenge.
-    let solution = signer.create_invalid_solution(&secret, challenge);
+    let solution = prover.create_invalid_solution(&secret, &binding(), challenge)?;
 
     // Test to make sure that the invalid solution is rejected.
-    assert!(!verifier.verify_solution(solution));
+    assert!(!verifier.verify_solution(solution)?);
+
+    Ok(())
+}
+
+fn test_valid_proof_for_group<G: PrimeOrderGroup>(group: &G) -> TestResult<()> {
+    // Create a non-interactive proof bound to some context.
+    let signer = Signer::from(group);
+    let secret = signer.create_random_secret();
+    let signature = signer.create_signature(&secret);
+    let proof = signer.create_proof(&secret, &binding(), b"context");
+
+    // Test to make sure that the proof passes.
+    assert!(verify_proof(
+        group,
+        &signature,
+        &proof,
+        &binding(),
+        b"context"
+    )?);
+
+    Ok(())
+}
+
+fn test_invalid_proof_for_group<G: PrimeOrderGroup>(group: &G) -> TestResult<()> {
+    // Create a non-interactive proof, then tamper with its solution.
+    let signer = Signer::from(group);
+    let secret = signer.create_random_secret();
+    let signature = signer.create_signature(&secret);
+    let mut proof = signer.create_proof(&secret, &binding(), b"context");
+    let solution = proof.solution.as_mut().ok_or(Error::ProofIncomplete)?;
+    let s = (BigUint::from_bytes_be(&solution.s) + 1u32) % group.order();
+    solution.s = s.to_bytes_be();
+
+    // Test to make sure that the tampered proof is rejected.
+    assert!(!verify_proof(
+        group,
+        &signature,
+        &proof,
+        &binding(),
+        b"context"
+    )?);
 
     Ok(())
 }
@@ -84,3 +153,666 @@ fn valid_256_bit_q_group_solution_passes() -> TestResult<()> {
 fn invalid_256_bit_q_group_solution_is_rejected() -> TestResult<()> {
     test_invalid_solution_for_group(&*MODP_2048_256_GROUP)
 }
+
+#[test]
+fn valid_ristretto255_group_solution_passes() -> TestResult<()> {
+    test_valid_solution_for_group(&*RISTRETTO255_GROUP)
+}
+
+#[test]
+fn invalid_ristretto255_group_solution_is_rejected() -> TestResult<()> {
+    test_invalid_solution_for_group(&*RISTRETTO255_GROUP)
+}
+
+#[test]
+fn valid_4_bit_q_group_proof_passes() -> TestResult<()> {
+    test_valid_proof_for_group(&*MODP_0005_004_GROUP)
+}
+
+#[test]
+fn valid_256_bit_q_group_proof_passes() -> TestResult<()> {
+    test_valid_proof_for_group(&*MODP_2048_256_GROUP)
+}
+
+#[test]
+fn invalid_256_bit_q_group_proof_is_rejected() -> TestResult<()> {
+    test_invalid_proof_for_group(&*MODP_2048_256_GROUP)
+}
+
+#[test]
+fn valid_ristretto255_group_proof_passes() -> TestResult<()> {
+    test_valid_proof_for_group(&*RISTRETTO255_GROUP)
+}
+
+#[test]
+fn invalid_ristretto255_group_proof_is_rejected() -> TestResult<()> {
+    test_invalid_proof_for_group(&*RISTRETTO255_GROUP)
+}
+
+fn test_hedged_proof_for_group<G: PrimeOrderGroup>(group: &G) -> TestResult<()> {
+    // Create two proofs of the same secret in the same context with hedged nonces.
+    let signer = Signer::from((group, NonceMode::Hedged));
+    let secret = signer.create_random_secret();
+    let signature = signer.create_signature(&secret);
+    let proof1 = signer.create_proof(&secret, &binding(), b"context");
+    let proof2 = signer.create_proof(&secret, &binding(), b"context");
+
+    // Test to make sure that both pass, and that the fresh entropy gave them
+    // different commitments.
+    assert!(verify_proof(
+        group,
+        &signature,
+        &proof1,
+        &binding(),
+        b"context"
+    )?);
+    assert!(verify_proof(
+        group,
+        &signature,
+        &proof2,
+        &binding(),
+        b"context"
+    )?);
+    assert_ne!(proof1.commitment, proof2.commitment);
+
+    Ok(())
+}
+
+#[test]
+fn hedged_256_bit_q_group_proof_passes() -> TestResult<()> {
+    test_hedged_proof_for_group(&*MODP_2048_256_GROUP)
+}
+
+#[test]
+fn hedged_ristretto255_group_proof_passes() -> TestResult<()> {
+    test_hedged_proof_for_group(&*RISTRETTO255_GROUP)
+}
+
+#[test]
+fn hedged_proofs_for_other_accounts_pass() -> TestResult<()> {
+    // The hedged nonce covers the whole statement, not just the context, so
+    // proving the same secret for another account in the same context works.
+    let group = &*MODP_2048_256_GROUP;
+    let signer = Signer::from((group, NonceMode::Hedged));
+    let secret = signer.create_random_secret();
+    let signature = signer.create_signature(&secret);
+    let other = Binding::from(("test-server", "bob"));
+    let proof1 = signer.create_proof(&secret, &binding(), b"context");
+    let proof2 = signer.create_proof(&secret, &other, b"context");
+
+    assert!(verify_proof(
+        group,
+        &signature,
+        &proof1,
+        &binding(),
+        b"context"
+    )?);
+    assert!(verify_proof(
+        group, &signature, &proof2, &other, b"context"
+    )?);
+
+    Ok(())
+}
+
+#[test]
+fn hedged_signer_commits_interactively_with_random_nonces() -> TestResult<()> {
+    // Interactive commitments have no context to hedge with, so they draw a
+    // fresh nonce every time even in hedged mode.
+    let group = &*MODP_2048_256_GROUP;
+    let signer = Signer::from((group, NonceMode::Hedged));
+    let secret = signer.create_random_secret();
+    let signature = signer.create_signature(&secret);
+    let (other, _) = signer.create_commitment();
+    let (commitment, prover) = signer.create_commitment();
+
+    assert_ne!(commitment, other);
+
+    let verifier =
+        Verifier::try_from((Arc::new(group.clone()), signature, commitment, &binding()))?;
+    let solution = prover.create_solution(&secret, &binding(), verifier.create_challenge())?;
+
+    assert!(verifier.verify_solution(solution)?);
+
+    Ok(())
+}
+
+#[test]
+fn proof_in_another_context_is_rejected() -> TestResult<()> {
+    let group = &*MODP_2048_256_GROUP;
+    let signer = Signer::from(group);
+    let secret = signer.create_random_secret();
+    let signature = signer.create_signature(&secret);
+    let proof = signer.create_proof(&secret, &binding(), b"context");
+
+    assert!(!verify_proof(
+        group,
+        &signature,
+        &proof,
+        &binding(),
+        b"other context"
+    )?);
+
+    Ok(())
+}
+
+fn test_wide_secret_for_group<G: PrimeOrderGroup>(group: &G, wide: &BigUint) -> TestResult<()> {
+    let signer = Signer::from(group);
+    let secret = Secret::new(wide.clone());
+    let reduced = Secret::new(wide % group.order());
+    let signature = signer.create_signature(&secret);
+    let proof = signer.create_proof(&secret, &binding(), b"context");
+
+    assert_eq!(signature, signer.create_signature(&reduced));
+    assert!(verify_proof(
+        group,
+        &signature,
+        &proof,
+        &binding(),
+        b"context"
+    )?);
+
+    Ok(())
+}
+
+#[test]
+fn secrets_for_a_larger_group_are_reduced() -> TestResult<()> {
+    // A secret isn't tied to a group, so one made for a group of larger order is
+    // reduced mod q instead of being too wide for the arithmetic.
+    test_wide_secret_for_group(&*MODP_1024_160_GROUP, &(RISTRETTO255_GROUP.order() - 1u32))?;
+    test_wide_secret_for_group(&*RISTRETTO255_GROUP, &(MODP_8192_8191_GROUP.order() - 1u32))
+}
+
+#[test]
+fn proof_for_another_signature_is_rejected() -> TestResult<()> {
+    let group = &*MODP_2048_256_GROUP;
+    let signer = Signer::from(group);
+    let secret = signer.create_random_secret();
+    let other_signature = signer.create_signature(&signer.create_random_secret());
+    let proof = signer.create_proof(&secret, &binding(), b"context");
+
+    assert!(!verify_proof(
+        group,
+        &other_signature,
+        &proof,
+        &binding(),
+        b"context"
+    )?);
+
+    Ok(())
+}
+
+#[test]
+fn proof_for_another_server_or_account_is_rejected() -> TestResult<()> {
+    let group = &*MODP_2048_256_GROUP;
+    let signer = Signer::from(group);
+    let secret = signer.create_random_secret();
+    let signature = signer.create_signature(&secret);
+    let proof = signer.create_proof(&secret, &binding(), b"context");
+
+    for other in [
+        Binding::from(("other-server", "alice")),
+        Binding::from(("test-server", "bob")),
+    ] {
+        assert!(!verify_proof(
+            group, &signature, &proof, &other, b"context"
+        )?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn challenge_for_another_server_or_account_is_refused() -> TestResult<()> {
+    // A challenge relayed from another server (or issued for another account)
+    // must not be answered, since its solution would authenticate there.
+    let group = &*MODP_2048_256_GROUP;
+    let signer = Signer::from(group);
+    let secret = signer.create_random_secret();
+
+    for other in [
+        Binding::from(("other-server", "alice")),
+        Binding::from(("test-server", "bob")),
+    ] {
+        let signature = signer.create_signature(&secret);
+        let (commitment, prover) = signer.create_commitment();
+        let verifier =
+            Verifier::try_from((Arc::new(group.clone()), signature, commitment, &other))?;
+
+        assert!(matches!(
+            prover.create_solution(&secret, &binding(), verifier.create_challenge()),
+            Err(Error::ChallengeNotBound)
+        ));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn proof_without_commitment_is_incomplete() {
+    let group = &*MODP_2048_256_GROUP;
+    let signer = Signer::from(group);
+    let secret = signer.create_random_secret();
+    let signature = signer.create_signature(&secret);
+    let mut proof = signer.create_proof(&secret, &binding(), b"context");
+    proof.commitment = None;
+
+    assert!(matches!(
+        verify_proof(group, &signature, &proof, &binding(), b"context"),
+        Err(Error::ProofIncomplete)
+    ));
+}
+
+#[test]
+fn verifier_rejects_signature_from_another_group() -> TestResult<()> {
+    // Sign with Ristretto255, but try to verify against a mod-p group.
+    let signer = Signer::from(&*RISTRETTO255_GROUP);
+    let secret = signer.create_random_secret();
+    let signature = signer.create_signature(&secret);
+    let (commitment, _prover) = signer.create_commitment();
+
+    let result = Verifier::try_from((
+        Arc::new(MODP_2048_256_GROUP.clone()),
+        signature,
+        commitment,
+        &binding(),
+    ));
+
+    assert!(matches!(result, Err(Error::GroupMismatch(_))));
+
+    Ok(())
+}
+
+#[test]
+fn ristretto255_proto_group_is_not_a_modp_group() {
+    let group = RISTRETTO255_GROUP.to_proto();
+
+    assert!(matches!(
+        Group::try_from(&group),
+        Err(Error::UnsupportedGroupKind)
+    ));
+}
+
+fn try_group(p: u32, q: u32, alpha: u32) -> Result<Group, Error> {
+    Group::try_from((
+        "test",
+        BigUint::from(p),
+        BigUint::from(q),
+        BigUint::from(alpha),
+    ))
+}
+
+#[test]
+fn preset_groups_are_valid() -> TestResult<()> {
+    for group in [
+        &*MODP_0005_004_GROUP,
+        &*MODP_1024_160_GROUP,
+        &*MODP_2048_224_GROUP,
+        &*MODP_2048_256_GROUP,
+    ] {
+        group.validate()?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn safe_prime_presets_are_named_by_their_width() {
+    // A couple of rounds are plenty for constants that are known to be prime.
+    for (group, id, bits) in [
+        (&*MODP_1536_1535_GROUP, "rfc3526-modp-1536", 1536),
+        (&*MODP_2048_2047_GROUP, "rfc3526-modp-2048", 2048),
+        (&*MODP_3072_3071_GROUP, "rfc3526-modp-3072", 3072),
+        (&*MODP_4096_4095_GROUP, "rfc3526-modp-4096", 4096),
+        (&*MODP_6144_6143_GROUP, "rfc3526-modp-6144", 6144),
+        (&*MODP_8192_8191_GROUP, "rfc3526-modp-8192", 8192),
+        (&*FFDHE_2048_GROUP, "rfc7919-ffdhe2048", 2048),
+        (&*FFDHE_3072_GROUP, "rfc7919-ffdhe3072", 3072),
+        (&*FFDHE_4096_GROUP, "rfc7919-ffdhe4096", 4096),
+        (&*FFDHE_6144_GROUP, "rfc7919-ffdhe6144", 6144),
+        (&*FFDHE_8192_GROUP, "rfc7919-ffdhe8192", 8192),
+    ] {
+        assert!(is_probable_prime(&group.q, 2), "{}", id);
+        assert_eq!(group.p, 2u32 * &group.q + 1u32, "{}", id);
+        assert!(is_probable_prime(&group.p, 2), "{}", id);
+        assert_eq!(group.bits(), (bits, bits - 1), "{}", id);
+        assert_eq!(group.id(), id);
+        assert_eq!(group.alpha, BigUint::from(2u32), "{}", id);
+        assert!(std::ptr::eq(preset_group(id).unwrap(), group), "{}", id);
+    }
+}
+
+#[test]
+fn safe_prime_group_proof_passes() -> TestResult<()> {
+    test_valid_proof_for_group(&*MODP_1536_1535_GROUP)
+}
+
+#[test]
+fn group_with_composite_p_is_rejected() {
+    // 35 - 1 = 34 = 2 * 17, so q divides p - 1, but p itself is composite.
+    assert!(matches!(try_group(35, 17, 2), Err(Error::PNotPrime)));
+}
+
+#[test]
+fn group_with_composite_q_is_rejected() {
+    // 23 - 1 = 22 = 2 * 11, so q = 22 divides p - 1 but isn't prime.
+    assert!(matches!(try_group(23, 22, 5), Err(Error::QNotPrime)));
+}
+
+#[test]
+fn group_of_order_two_is_rejected() {
+    // 4 = -1 has order 2 mod 5, which leaves no generator but alpha for beta.
+    assert!(matches!(try_group(5, 2, 4), Err(Error::QTooSmall)));
+}
+
+#[test]
+fn group_with_p_over_8192_bits_is_rejected() {
+    // The width of p is checked before anything else about it.
+    let p: BigUint = (BigUint::from(1u32) << 8194) + 1u32;
+    let mut group = MODP_1024_160_GROUP.to_proto();
+    group.p = p.to_bytes_be();
+    group.q = BigUint::from(3u32).to_bytes_be();
+
+    assert!(matches!(Group::try_from(&group), Err(Error::PTooLarge)));
+    assert!(matches!(
+        Group::try_from(("test", p, BigUint::from(3u32), BigUint::from(2u32))),
+        Err(Error::PTooLarge)
+    ));
+}
+
+#[test]
+fn group_where_q_does_not_divide_p_minus_one_is_rejected() {
+    assert!(matches!(
+        try_group(23, 7, 2),
+        Err(Error::QDoesNotDividePMinusOne)
+    ));
+}
+
+#[test]
+fn group_with_alpha_of_wrong_order_is_rejected() {
+    // 5 generates all of Z_23^*, which has order 22 rather than 11.
+    assert!(matches!(try_group(23, 11, 5), Err(Error::InvalidAlpha)));
+    assert!(matches!(try_group(23, 11, 1), Err(Error::InvalidAlpha)));
+    assert!(matches!(try_group(23, 11, 25), Err(Error::InvalidAlpha)));
+}
+
+#[test]
+fn proto_group_with_beta_outside_subgroup_is_rejected() {
+    let mut group = MODP_1024_160_GROUP.to_proto();
+    group.beta = BigUint::from(2u32).to_bytes_be();
+
+    assert!(matches!(Group::try_from(&group), Err(Error::InvalidBeta)));
+}
+
+#[test]
+fn beta_is_hashed_from_the_seed() -> TestResult<()> {
+    // Computed independently, with a reference implementation in Python.
+    let beta_1024_160 = BigUint::parse_bytes(
+        b"16ab57d79ae7aced0103357ecaaffbae1d2ba93aac8ad323c2b04d69b0b64140\
+          fb7cf55ddd86a682bcdb2c211bfb9e1ff6ec83a2ea21fdb591f4a767e7213651\
+          4c7873ade26a4de477690370e5abd45688841ace83de7693b583fad52dd4b556\
+          f019fb710fce56274266d8f1bb480a4fa6626f5793f940a24b5b651c00e81415",
+        16,
+    )
+    .unwrap();
+
+    assert_eq!(*MODP_0005_004_GROUP.beta(), BigUint::from(3u32));
+    assert_eq!(*MODP_1024_160_GROUP.beta(), beta_1024_160);
+    assert_eq!(
+        MODP_1024_160_GROUP.beta_seed(),
+        "zkp-chaum-pedersen/rfc5114-modp-1024-160/beta"
+    );
+
+    // The first count for this seed hashes to 1, so the second is used.
+    let group = Group::try_from((
+        "test",
+        BigUint::from(23u32),
+        BigUint::from(11u32),
+        BigUint::from(2u32),
+        "d",
+    ))?;
+    assert_eq!(*group.beta(), BigUint::from(6u32));
+
+    Ok(())
+}
+
+#[test]
+fn proto_group_with_beta_not_from_seed_is_rejected() {
+    let group = &*MODP_1024_160_GROUP;
+
+    // alpha^2 has order q, but its discrete log is known.
+    let mut substituted = group.to_proto();
+    substituted.beta = group.encode(&group.exp(group.alpha(), &BigUint::from(2u32)));
+
+    let mut reseeded = group.to_proto();
+    reseeded.beta_seed = "another seed".to_string();
+
+    for proto in [substituted, reseeded] {
+        assert!(matches!(
+            Group::try_from(&proto),
+            Err(Error::BetaNotFromSeed)
+        ));
+    }
+}
+
+#[test]
+fn fixed_base_tables_give_the_same_powers() -> TestResult<()> {
+    for preset in [&*MODP_0005_004_GROUP, &*MODP_2048_224_GROUP] {
+        // Groups decoded from protobuf don't carry tables.
+        let plain = Group::try_from(&preset.to_proto())?;
+        let exponent = preset.random_scalar();
+
+        let y = plain.exp(plain.alpha(), &plain.random_scalar());
+        let c = preset.random_scalar();
+
+        for base in [preset.alpha(), preset.beta()] {
+            assert_eq!(preset.exp(base, &exponent), plain.exp(base, &exponent));
+            assert_eq!(
+                preset.exp_secret(base, &exponent),
+                plain.exp_secret(base, &exponent)
+            );
+            assert_eq!(
+                preset.double_exp(base, &exponent, &y, &c),
+                plain.double_exp(base, &exponent, &y, &c)
+            );
+        }
+
+        // Exponents too wide for the tables fall back to modpow.
+        let wide = preset.order() << 8;
+        assert_eq!(
+            preset.exp(preset.alpha(), &wide),
+            plain.exp(plain.alpha(), &wide)
+        );
+    }
+
+    Ok(())
+}
+
+fn test_double_exp_for_group<G: PrimeOrderGroup>(group: &G) {
+    let y = group.exp(group.alpha(), &group.random_scalar());
+    let (a, b) = (group.random_scalar(), group.random_scalar());
+    let zero = BigUint::from(0u32);
+
+    for g in [group.alpha(), group.beta(), &y] {
+        for (a, b) in [(&a, &b), (&zero, &b), (&a, &zero)] {
+            assert_eq!(
+                group.double_exp(g, a, &y, b),
+                group.mul(&group.exp(g, a), &group.exp(&y, b))
+            );
+        }
+    }
+}
+
+#[test]
+fn double_exp_matches_naive_formula() -> TestResult<()> {
+    test_double_exp_for_group(&*MODP_0005_004_GROUP);
+    test_double_exp_for_group(&*MODP_1024_160_GROUP);
+    test_double_exp_for_group(&Group::try_from(&MODP_2048_256_GROUP.to_proto())?);
+    test_double_exp_for_group(&*RISTRETTO255_GROUP);
+
+    Ok(())
+}
+
+#[test]
+fn proto_group_round_trips() -> TestResult<()> {
+    let group = Group::try_from(&MODP_2048_256_GROUP.to_proto())?;
+
+    assert!(MODP_2048_256_GROUP.matches(&group.to_proto()));
+
+    Ok(())
+}
+
+#[test]
+fn canonical_elements_are_decoded() -> TestResult<()> {
+    let group = &*MODP_1024_160_GROUP;
+    let one = BigUint::from(1u32);
+
+    assert_eq!(group.decode(&group.encode(&one))?, one);
+    assert_eq!(group.decode(&group.encode(group.beta()))?, *group.beta());
+
+    Ok(())
+}
+
+#[test]
+fn out_of_range_elements_are_rejected() {
+    let group = &*MODP_1024_160_GROUP;
+
+    for element in [BigUint::from(0u32), group.p.clone(), &group.p + 1u32] {
+        assert!(matches!(
+            group.decode(&element.to_bytes_be()),
+            Err(Error::ElementOutOfRange)
+        ));
+    }
+}
+
+#[test]
+fn elements_outside_subgroup_are_rejected() {
+    // p - 1 has order 2, so it can't be in a subgroup of odd prime order q.
+    let group = &*MODP_1024_160_GROUP;
+    let element = &group.p - 1u32;
+
+    assert!(matches!(
+        group.decode(&element.to_bytes_be()),
+        Err(Error::ElementNotInSubgroup)
+    ));
+}
+
+#[test]
+fn non_canonical_encodings_are_rejected() {
+    let group = &*MODP_1024_160_GROUP;
+    let mut padded = vec![0u8];
+    padded.extend(group.encode(group.alpha()));
+
+    assert!(matches!(
+        group.decode(&[]),
+        Err(Error::NonCanonicalEncoding)
+    ));
+    assert!(matches!(
+        group.decode(&padded),
+        Err(Error::NonCanonicalEncoding)
+    ));
+    assert!(matches!(
+        group.decode_scalar(&[0, 1]),
+        Err(Error::NonCanonicalEncoding)
+    ));
+}
+
+#[test]
+fn out_of_range_scalars_are_rejected() -> TestResult<()> {
+    let group = &*MODP_1024_160_GROUP;
+    let q_minus_one = group.order() - 1u32;
+
+    assert_eq!(group.decode_scalar(&[0])?, BigUint::from(0u32));
+    assert_eq!(
+        group.decode_scalar(&q_minus_one.to_bytes_be())?,
+        q_minus_one
+    );
+    assert!(matches!(
+        group.decode_scalar(&group.order().to_bytes_be()),
+        Err(Error::ScalarOutOfRange)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn signer_rejects_out_of_range_challenge() {
+    let group = &*MODP_1024_160_GROUP;
+    let signer = Signer::from(group);
+    let secret = signer.create_random_secret();
+    let (_commitment, prover) = signer.create_commitment();
+    let challenge = Challenge {
+        c: (group.order() + 5u32).to_bytes_be(),
+        nonce: Vec::new(),
+    };
+
+    assert!(matches!(
+        prover.create_solution(&secret, &binding(), challenge),
+        Err(Error::ScalarOutOfRange)
+    ));
+}
+
+#[test]
+fn verifier_rejects_out_of_range_solution() -> TestResult<()> {
+    let group = &*MODP_1024_160_GROUP;
+    let signer = Signer::from(group);
+    let secret = signer.create_random_secret();
+    let signature = signer.create_signature(&secret);
+    let (commitment, prover) = signer.create_commitment();
+    let verifier =
+        Verifier::try_from((Arc::new(group.clone()), signature, commitment, &binding()))?;
+
+    // s + q satisfies the same equations as s, but isn't a canonical scalar.
+    let solution = prover.create_solution(&secret, &binding(), verifier.create_challenge())?;
+    let s = BigUint::from_bytes_be(&solution.s) + group.order();
+    let solution = Solution { s: s.to_bytes_be() };
+
+    assert!(matches!(
+        verifier.verify_solution(solution),
+        Err(Error::ScalarOutOfRange)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn verifier_rejects_commitment_outside_subgroup() -> TestResult<()> {
+    let group = &*MODP_1024_160_GROUP;
+    let signer = Signer::from(group);
+    let secret = signer.create_random_secret();
+    let signature = signer.create_signature(&secret);
+    let (mut commitment, _prover) = signer.create_commitment();
+    commitment.r2 = (&group.p - 1u32).to_bytes_be();
+
+    let result = Verifier::try_from((Arc::new(group.clone()), signature, commitment, &binding()));
+
+    assert!(matches!(result, Err(Error::ElementNotInSubgroup)));
+
+    Ok(())
+}
+
+#[test]
+fn demo_groups_are_only_presets_with_their_feature() {
+    for id in INSECURE_DEMO_GROUP_IDS {
+        assert_eq!(
+            preset_group(id).is_some(),
+            cfg!(feature = "insecure-demo-groups")
+        );
+    }
+}
+
+#[test]
+fn security_levels_follow_nist_estimates() {
+    for (group, bits) in [
+        (&*MODP_0005_004_GROUP, 0),
+        (&*MODP_1024_160_GROUP, 80),
+        (&*MODP_2048_224_GROUP, 112),
+        (&*MODP_2048_256_GROUP, 112),
+        (&*MODP_1536_1535_GROUP, 80),
+        (&*FFDHE_3072_GROUP, 128),
+        (&*MODP_8192_8191_GROUP, 192),
+    ] {
+        assert_eq!(group.security_bits(), bits, "{}", group.id());
+    }
+
+    assert_eq!(RISTRETTO255_GROUP.security_bits(), 128);
+}
@@ -0,0 +1,87 @@
+//! A Merlin-style transcript for Fiat-Shamir challenges. The prover and the
+//! verifier each build the same transcript, appending labeled messages, group
+//! elements and scalars in the same order, and squeeze challenges out of it;
+//! any difference in what they saw (or in which order) gives a different
+//! challenge.
+
+use crate::zkp::PrimeOrderGroup;
+use num_bigint::BigUint;
+use prost::Message;
+use sha2::{Digest, Sha512};
+
+#[cfg(test)]
+mod test;
+
+/// A running hash of everything a proof's challenges depend on.
+#[derive(Clone)]
+pub struct Transcript {
+    hasher: Sha512,
+}
+
+impl Transcript {
+    /// Starts a transcript that's domain-separated by the protocol's name (and
+    /// version), so that transcripts of different protocols never coincide.
+    pub fn new(protocol: &str) -> Self {
+        let mut transcript = Self {
+            hasher: Sha512::new(),
+        };
+        transcript.append_message(b"protocol", protocol.as_bytes());
+
+        transcript
+    }
+
+    /// Appends a labeled message. The label and the message are each
+    /// length-prefixed, so no two sequences of messages are hashed the same.
+    pub fn append_message(&mut self, label: &[u8], message: &[u8]) {
+        for part in [label, message] {
+            self.hasher.update((part.len() as u64).to_be_bytes());
+            self.hasher.update(part);
+        }
+    }
+
+    /// Appends the group's id and full parameters, so that the challenges are
+    /// bound to the group that the proof is made in.
+    pub fn append_group<G: PrimeOrderGroup>(&mut self, group: &G) {
+        self.append_message(b"group-id", group.id().as_bytes());
+        self.append_message(b"group", &group.to_proto().encode_to_vec());
+    }
+
+    /// Appends a labeled group element, in its wire encoding.
+    pub fn append_element<G: PrimeOrderGroup>(
+        &mut self,
+        group: &G,
+        label: &[u8],
+        element: &G::Element,
+    ) {
+        self.append_message(label, &group.encode(element));
+    }
+
+    /// Appends a labeled scalar, as a big-endian integer as wide as `q`.
+    pub fn append_scalar<G: PrimeOrderGroup>(&mut self, group: &G, label: &[u8], scalar: &BigUint) {
+        let width = (group.order().bits() as usize).div_ceil(8);
+        let bytes = scalar.to_bytes_be();
+        let mut padded = vec![0u8; width.saturating_sub(bytes.len())];
+        padded.extend_from_slice(&bytes);
+
+        self.append_message(label, &padded);
+    }
+
+    /// Squeezes labeled challenge bytes out of the transcript, then appends
+    /// them, so that later challenges depend on these.
+    pub fn challenge_bytes(&mut self, label: &[u8]) -> [u8; 64] {
+        self.append_message(b"challenge", label);
+
+        let bytes: [u8; 64] = self.hasher.clone().finalize().into();
+        self.append_message(label, &bytes);
+
+        bytes
+    }
+
+    /// Squeezes a labeled challenge in `[0, q)` out of the transcript.
+    ///
+    /// The 512-bit digest is reduced mod `q`, which leaves a negligible bias for
+    /// any `q` of up to 256 bits (and a 512-bit challenge space for wider ones).
+    pub fn challenge_scalar<G: PrimeOrderGroup>(&mut self, group: &G, label: &[u8]) -> BigUint {
+        BigUint::from_bytes_be(&self.challenge_bytes(label)) % group.order()
+    }
+}
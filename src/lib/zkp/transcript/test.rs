@@ -0,0 +1,90 @@
+use crate::zkp::{
+    ristretto::RISTRETTO255_GROUP, transcript::Transcript, PrimeOrderGroup, MODP_1024_160_GROUP,
+    MODP_2048_256_GROUP,
+};
+use num_bigint::BigUint;
+
+#[test]
+fn same_transcripts_give_same_challenges() {
+    let group = &*MODP_1024_160_GROUP;
+    let build = || {
+        let mut transcript = Transcript::new("test");
+        transcript.append_group(group);
+        transcript.append_element(group, b"alpha", group.alpha());
+        transcript.append_scalar(group, b"x", &BigUint::from(7u32));
+
+        transcript
+    };
+
+    // The prover and verifier squeeze the same sequence of challenges.
+    let (mut prover, mut verifier) = (build(), build());
+
+    for label in [b"c1", b"c2"] {
+        assert_eq!(
+            prover.challenge_scalar(group, label),
+            verifier.challenge_scalar(group, label)
+        );
+    }
+}
+
+#[test]
+fn challenges_depend_on_protocol_labels_and_order() {
+    let group = &*MODP_1024_160_GROUP;
+    let challenge = |protocol: &str, messages: &[(&[u8], &[u8])]| {
+        let mut transcript = Transcript::new(protocol);
+        for (label, message) in messages {
+            transcript.append_message(label, message);
+        }
+
+        transcript.challenge_scalar(group, b"c")
+    };
+
+    let c = challenge("test", &[(b"a", b"1"), (b"b", b"2")]);
+
+    assert_ne!(c, challenge("other", &[(b"a", b"1"), (b"b", b"2")]));
+    assert_ne!(c, challenge("test", &[(b"a", b"1"), (b"c", b"2")]));
+    assert_ne!(c, challenge("test", &[(b"b", b"2"), (b"a", b"1")]));
+    // Moving a byte across the boundary between label and message.
+    assert_ne!(c, challenge("test", &[(b"a1", b""), (b"b", b"2")]));
+}
+
+#[test]
+fn successive_challenges_differ() {
+    let group = &*RISTRETTO255_GROUP;
+    let mut transcript = Transcript::new("test");
+
+    let c1 = transcript.challenge_scalar(group, b"c");
+    let c2 = transcript.challenge_scalar(group, b"c");
+
+    assert_ne!(c1, c2);
+}
+
+#[test]
+fn scalars_are_fixed_width() {
+    // Without padding, 0x0102 as one scalar would read the same as 0x01, 0x02.
+    let group = &*MODP_2048_256_GROUP;
+    let mut one = Transcript::new("test");
+    one.append_scalar(group, b"x", &BigUint::from(0x0102u32));
+
+    let mut other = Transcript::new("test");
+    other.append_message(b"x", &[0x01, 0x02]);
+
+    assert_ne!(
+        one.challenge_scalar(group, b"c"),
+        other.challenge_scalar(group, b"c")
+    );
+}
+
+#[test]
+fn challenges_are_below_the_order() {
+    for group in [&*MODP_1024_160_GROUP, &*MODP_2048_256_GROUP] {
+        let mut transcript = Transcript::new("test");
+
+        for _ in 0..16 {
+            assert!(transcript.challenge_scalar(group, b"c") < *group.order());
+        }
+    }
+
+    let mut transcript = Transcript::new("test");
+    assert!(transcript.challenge_scalar(&*RISTRETTO255_GROUP, b"c") < *RISTRETTO255_GROUP.order());
+}
@@ -0,0 +1,152 @@
+use crate::{
+    grpc::auth::{Proof, Signature, Solution},
+    zkp::{
+        fiat_shamir::Binding,
+        verifier::{check_group, Claim, Verifier},
+        Error, Group, PrimeOrderGroup,
+    },
+};
+use num_bigint::{BigUint, RandBigInt};
+use std::sync::Arc;
+
+#[cfg(test)]
+mod test;
+
+/// The width, in bits, of the random weights that claims are combined with. A
+/// batch with any invalid claim passes with probability at most `2^-128` (or
+/// `1/q`, for smaller groups).
+const WEIGHT_BITS: u64 = 128;
+
+/// Verifies many solutions and proofs over one group at once.
+///
+/// Each claim's two equations, `r1 = alpha^s * y1^c` and `r2 = beta^s * y2^c`,
+/// are raised to independent random weights and multiplied together, so the
+/// whole batch comes down to checking that a single multi-exponentiation is the
+/// identity. If it isn't, every claim is checked on its own to pinpoint the
+/// invalid ones. This is only sound because decoding has already checked that
+/// every element lies in the order-`q` group.
+#[derive(Debug)]
+pub struct BatchVerifier<G: PrimeOrderGroup = Group> {
+    group: Arc<G>,
+    claims: Vec<Claim<G>>,
+}
+
+impl<G: PrimeOrderGroup> BatchVerifier<G> {
+    /// Adds an interactive verifier's claim, with the solution to its challenge.
+    /// Fails if the verifier is for another group (even one with the same id),
+    /// or if `s` isn't a canonically encoded scalar in `[0, q)`.
+    pub fn add_solution(
+        &mut self,
+        verifier: &Verifier<G>,
+        solution: Solution,
+    ) -> Result<(), Error> {
+        if !Arc::ptr_eq(&verifier.group, &self.group)
+            && !self.group.matches(&verifier.group.to_proto())
+        {
+            return Err(Error::GroupMismatch(verifier.group.id().to_string()));
+        }
+
+        self.claims.push(Claim {
+            y1: verifier.y1.clone(),
+            y2: verifier.y2.clone(),
+            r1: verifier.r1.clone(),
+            r2: verifier.r2.clone(),
+            c: verifier.c.clone(),
+            s: self.group.decode_scalar(&solution.s)?,
+        });
+
+        Ok(())
+    }
+
+    /// Adds a non-interactive proof against the given signature, binding and
+    /// context, as checked by [`verify_proof`](super::verify_proof). Fails if
+    /// the signature was made against another group, even one with the same id.
+    pub fn add_proof(
+        &mut self,
+        signature: &Signature,
+        proof: &Proof,
+        binding: &Binding,
+        context: &[u8],
+    ) -> Result<(), Error> {
+        check_group(&*self.group, signature)?;
+
+        self.claims.push(Claim::from_proof(
+            &*self.group,
+            signature,
+            proof,
+            binding,
+            context,
+        )?);
+
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.claims.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.claims.is_empty()
+    }
+
+    /// Verifies every claim added so far, returning whether each one holds, in
+    /// the order they were added.
+    pub fn verify(&self) -> Vec<bool> {
+        if self.verify_all() {
+            vec![true; self.claims.len()]
+        } else {
+            self.claims
+                .iter()
+                .map(|claim| claim.check(&*self.group))
+                .collect()
+        }
+    }
+
+    /// Checks that the product, over every claim, of
+    /// `(alpha^s * y1^c / r1)^z * (beta^s * y2^c / r2)^w` is the identity, for
+    /// fresh random weights `z` and `w`.
+    fn verify_all(&self) -> bool {
+        let group = &*self.group;
+        let q = group.order();
+
+        let mut bases = Vec::with_capacity(4 * self.claims.len() + 2);
+        let mut exponents = Vec::with_capacity(4 * self.claims.len() + 2);
+        let (mut alpha_exponent, mut beta_exponent) = (BigUint::from(0u32), BigUint::from(0u32));
+
+        for claim in self.claims.iter() {
+            let (z, w) = (self.weight(), self.weight());
+
+            alpha_exponent += &z * &claim.s;
+            beta_exponent += &w * &claim.s;
+
+            bases.extend([
+                claim.y1.clone(),
+                claim.y2.clone(),
+                claim.r1.clone(),
+                claim.r2.clone(),
+            ]);
+            exponents.extend([(&z * &claim.c) % q, (&w * &claim.c) % q, q - z, q - w]);
+        }
+
+        bases.extend([group.alpha().clone(), group.beta().clone()]);
+        exponents.extend([alpha_exponent % q, beta_exponent % q]);
+
+        group.multi_exp(&bases, &exponents) == group.identity()
+    }
+
+    /// Picks a random weight in `[1, min(q, 2^128))`.
+    fn weight(&self) -> BigUint {
+        let bound = (BigUint::from(1u32) << WEIGHT_BITS).min(self.group.order().clone());
+
+        rand::thread_rng().gen_biguint_range(&BigUint::from(1u32), &bound)
+    }
+}
+
+impl<G: PrimeOrderGroup> From<Arc<G>> for BatchVerifier<G> {
+    fn from(group: Arc<G>) -> Self {
+        Self {
+            group,
+            claims: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use crate::{
+    grpc::auth::{Proof, Signature},
+    zkp::{
+        ristretto::RISTRETTO255_GROUP, signer::Signer, test::binding,
+        verifier::batch::BatchVerifier, verifier::Verifier, Error, Group, PrimeOrderGroup,
+        MODP_0005_004_GROUP, MODP_1024_160_GROUP, MODP_2048_256_GROUP,
+    },
+};
+use std::sync::Arc;
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+const BATCH_SIZE: usize = 8;
+
+/// Creates `n` signatures with proofs over the given context, each from a
+/// different secret.
+fn signed_proofs<G: PrimeOrderGroup>(group: &G, n: usize) -> Vec<(Signature, Proof)> {
+    let signer = Signer::from(group);
+
+    (0..n)
+        .map(|_| {
+            let secret = signer.create_random_secret();
+
+            (
+                signer.create_signature(&secret),
+                signer.create_proof(&secret, &binding(), b"context"),
+            )
+        })
+        .collect()
+}
+
+fn test_batch_for_group<G: PrimeOrderGroup + Clone>(group: &G) -> TestResult<()> {
+    let mut batch = BatchVerifier::from(Arc::new(group.clone()));
+    let proofs = signed_proofs(group, BATCH_SIZE);
+
+    for (signature, proof) in proofs.iter() {
+        batch.add_proof(signature, proof, &binding(), b"context")?;
+    }
+
+    assert_eq!(batch.len(), BATCH_SIZE);
+    assert_eq!(batch.verify(), vec![true; BATCH_SIZE]);
+
+    // Swap two signatures, so that each of their proofs is for the other.
+    let mut batch = BatchVerifier::from(Arc::new(group.clone()));
+
+    for (i, (_, proof)) in proofs.iter().enumerate() {
+        let j = match i {
+            2 => 5,
+            5 => 2,
+            i => i,
+        };
+        batch.add_proof(&proofs[j].0, proof, &binding(), b"context")?;
+    }
+
+    let expected: Vec<bool> = (0..BATCH_SIZE).map(|i| i != 2 && i != 5).collect();
+    assert_eq!(batch.verify(), expected);
+
+    Ok(())
+}
+
+#[test]
+fn batch_of_modp_proofs_is_verified() -> TestResult<()> {
+    test_batch_for_group(&*MODP_1024_160_GROUP)
+}
+
+#[test]
+fn batch_of_ristretto255_proofs_is_verified() -> TestResult<()> {
+    test_batch_for_group(&*RISTRETTO255_GROUP)
+}
+
+#[test]
+fn invalid_solution_is_pinpointed() -> TestResult<()> {
+    let group = Arc::new(MODP_2048_256_GROUP.clone());
+    let signer = Signer::from(&*group);
+    let mut batch = BatchVerifier::from(group.clone());
+
+    for i in 0..BATCH_SIZE {
+        let secret = signer.create_random_secret();
+        let signature = signer.create_signature(&secret);
+        let (commitment, prover) = signer.create_commitment();
+
+        let verifier = Verifier::try_from((group.clone(), signature, commitment, &binding()))?;
+        let challenge = verifier.create_challenge();
+        let solution = if i == 3 {
+            prover.create_invalid_solution(&secret, &binding(), challenge)?
+        } else {
+            prover.create_solution(&secret, &binding(), challenge)?
+        };
+
+        batch.add_solution(&verifier, solution)?;
+    }
+
+    let expected: Vec<bool> = (0..BATCH_SIZE).map(|i| i != 3).collect();
+    assert_eq!(batch.verify(), expected);
+
+    Ok(())
+}
+
+#[test]
+fn proof_in_another_context_is_pinpointed() -> TestResult<()> {
+    let group = &*RISTRETTO255_GROUP;
+    let mut batch = BatchVerifier::from(Arc::new(group.clone()));
+
+    for (i, (signature, proof)) in signed_proofs(group, BATCH_SIZE).iter().enumerate() {
+        let context: &[u8] = if i == 0 {
+            b"another context"
+        } else {
+            b"context"
+        };
+        batch.add_proof(signature, proof, &binding(), context)?;
+    }
+
+    let expected: Vec<bool> = (0..BATCH_SIZE).map(|i| i != 0).collect();
+    assert_eq!(batch.verify(), expected);
+
+    Ok(())
+}
+
+#[test]
+fn empty_batch_is_verified() {
+    let batch = BatchVerifier::from(Arc::new(MODP_1024_160_GROUP.clone()));
+
+    assert!(batch.is_empty());
+    assert!(batch.verify().is_empty());
+}
+
+#[test]
+fn claims_from_another_group_are_rejected() -> TestResult<()> {
+    let mut batch = BatchVerifier::from(Arc::new(MODP_1024_160_GROUP.clone()));
+
+    let group = Arc::new(MODP_2048_256_GROUP.clone());
+    let signer = Signer::from(&*group);
+    let secret = signer.create_random_secret();
+    let signature = signer.create_signature(&secret);
+    let proof = signer.create_proof(&secret, &binding(), b"context");
+    let (commitment, prover) = signer.create_commitment();
+    let verifier = Verifier::try_from((group.clone(), signature.clone(), commitment, &binding()))?;
+    let solution = prover.create_solution(&secret, &binding(), verifier.create_challenge())?;
+
+    assert!(matches!(
+        batch.add_proof(&signature, &proof, &binding(), b"context"),
+        Err(Error::GroupMismatch(_))
+    ));
+    assert!(matches!(
+        batch.add_solution(&verifier, solution),
+        Err(Error::GroupMismatch(_))
+    ));
+    assert!(batch.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn claims_from_a_group_with_the_same_id_are_rejected() -> TestResult<()> {
+    // A custom group may reuse a preset's id with other parameters; its claims
+    // would be meaningless over the preset's modulus and generators.
+    let mut batch = BatchVerifier::from(Arc::new(MODP_1024_160_GROUP.clone()));
+
+    let toy = &*MODP_0005_004_GROUP;
+    let group = Arc::new(Group::try_from((
+        MODP_1024_160_GROUP.id(),
+        toy.p.clone(),
+        toy.q.clone(),
+        toy.alpha.clone(),
+    ))?);
+    let signer = Signer::from(&*group);
+    let secret = signer.create_random_secret();
+    let signature = signer.create_signature(&secret);
+    let proof = signer.create_proof(&secret, &binding(), b"context");
+    let (commitment, prover) = signer.create_commitment();
+    let verifier = Verifier::try_from((group.clone(), signature.clone(), commitment, &binding()))?;
+    let solution = prover.create_solution(&secret, &binding(), verifier.create_challenge())?;
+
+    assert!(matches!(
+        batch.add_proof(&signature, &proof, &binding(), b"context"),
+        Err(Error::GroupMismatch(_))
+    ));
+    assert!(matches!(
+        batch.add_solution(&verifier, solution),
+        Err(Error::GroupMismatch(_))
+    ));
+    assert!(batch.is_empty());
+
+    Ok(())
+}
@@ -1,56 +1,75 @@
 use crate::{
-    grpc::auth::{Challenge, Commitment, Signature, Solution},
-    zkp::{Error, Group},
+    grpc::auth::{Challenge, Commitment, Proof, Signature, Solution},
+    zkp::{
+        fiat_shamir::{derive_challenge, Binding},
+        Error, Group, PrimeOrderGroup,
+    },
 };
-use num_bigint::{BigUint, RandBigInt};
+use num_bigint::BigUint;
+use rand::RngCore;
+use std::sync::Arc;
+
+pub mod batch;
+
+/// The length of the random nonce that an interactive challenge is derived
+/// from, in bytes.
+pub const CHALLENGE_NONCE_LEN: usize = 32;
 
 #[derive(Debug)]
-pub struct Verifier {
-    group: Group,
-    y1: BigUint,
-    y2: BigUint,
-    r1: BigUint,
-    r2: BigUint,
+pub struct Verifier<G: PrimeOrderGroup = Group> {
+    group: Arc<G>,
+    y1: G::Element,
+    y2: G::Element,
+    r1: G::Element,
+    r2: G::Element,
     c: BigUint,
+    nonce: Vec<u8>,
 }
 
-impl Verifier {
+impl<G: PrimeOrderGroup> Verifier<G> {
     pub fn create_challenge(&self) -> Challenge {
         Challenge {
             c: self.c.to_bytes_be(),
+            nonce: self.nonce.clone(),
         }
     }
 
     /// Verifies that the given solution satisifes the commitment, i.e.
-    /// checks that `r1 = alpha^s * y1^c` and `r2 = beta^s * y2^c`.
-    pub fn verify_solution(&self, solution: Solution) -> bool {
-        let one = BigUint::from(1u32);
-        let s = BigUint::from_bytes_be(&solution.s);
-
-        let c1 = self.r1
-            == (self.group.alpha.modpow(&s, &self.group.p)
-                * self.y1.modpow(&self.c, &self.group.p))
-            .modpow(&one, &self.group.p);
+    /// checks that `r1 = alpha^s * y1^c` and `r2 = beta^s * y2^c`. Fails if
+    /// `s` isn't a canonically encoded scalar in `[0, q)`.
+    pub fn verify_solution(&self, solution: Solution) -> Result<bool, Error> {
+        let s = self.group.decode_scalar(&solution.s)?;
 
-        let c2 = self.r2
-            == (self.group.beta.modpow(&s, &self.group.p) * self.y2.modpow(&self.c, &self.group.p))
-                .modpow(&one, &self.group.p);
-
-        c1 && c2
+        Ok(check_solution(
+            &*self.group,
+            (&self.y1, &self.y2),
+            (&self.r1, &self.r2),
+            &self.c,
+            &s,
+        ))
     }
 }
 
-impl TryFrom<(Signature, Commitment)> for Verifier {
+impl<G: PrimeOrderGroup> TryFrom<(Arc<G>, Signature, Commitment, &Binding)> for Verifier<G> {
     type Error = Error;
 
-    fn try_from((signature, commitment): (Signature, Commitment)) -> Result<Self, Self::Error> {
-        let group = Group::from(&signature.group.ok_or_else(|| Error::GroupNotSpecified)?);
+    /// Creates a verifier for the given signature and commitment, using the
+    /// (registered) group that the signature was made against. The challenge is
+    /// derived from the transcript, bound to the server and account, and a fresh
+    /// random nonce.
+    fn try_from(
+        (group, signature, commitment, binding): (Arc<G>, Signature, Commitment, &Binding),
+    ) -> Result<Self, Self::Error> {
+        check_group(&*group, &signature)?;
+
+        let y1 = group.decode(&signature.y1)?;
+        let y2 = group.decode(&signature.y2)?;
+        let r1 = group.decode(&commitment.r1)?;
+        let r2 = group.decode(&commitment.r2)?;
 
-        let y1 = BigUint::from_bytes_be(&signature.y1);
-        let y2 = BigUint::from_bytes_be(&signature.y2);
-        let r1 = BigUint::from_bytes_be(&commitment.r1);
-        let r2 = BigUint::from_bytes_be(&commitment.r2);
-        let c = rand::thread_rng().gen_biguint_below(&group.q);
+        let mut nonce = vec![0u8; CHALLENGE_NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let c = derive_challenge(&*group, binding, (&y1, &y2), (&r1, &r2), &nonce);
 
         Ok(Self {
             group,
@@ -59,6 +78,101 @@ impl TryFrom<(Signature, Commitment)> for Verifier {
             r1,
             r2,
             c,
+            nonce,
+        })
+    }
+}
+
+/// Verifies a non-interactive proof against the given signature, binding and
+/// context, without any verifier state: the challenge is recomputed from the
+/// transcript, and the solution checked against it.
+pub fn verify_proof<G: PrimeOrderGroup>(
+    group: &G,
+    signature: &Signature,
+    proof: &Proof,
+    binding: &Binding,
+    context: &[u8],
+) -> Result<bool, Error> {
+    check_group(group, signature)?;
+
+    Ok(Claim::from_proof(group, signature, proof, binding, context)?.check(group))
+}
+
+/// The decoded public values of a single proof: the signature `(y1, y2)`, the
+/// commitment `(r1, r2)`, the challenge `c` and the solution `s`.
+#[derive(Debug)]
+struct Claim<G: PrimeOrderGroup> {
+    y1: G::Element,
+    y2: G::Element,
+    r1: G::Element,
+    r2: G::Element,
+    c: BigUint,
+    s: BigUint,
+}
+
+impl<G: PrimeOrderGroup> Claim<G> {
+    /// Decodes a non-interactive proof, recomputing its challenge from the
+    /// binding and context. The signature's group must already have been checked.
+    fn from_proof(
+        group: &G,
+        signature: &Signature,
+        proof: &Proof,
+        binding: &Binding,
+        context: &[u8],
+    ) -> Result<Self, Error> {
+        let commitment = proof.commitment.as_ref().ok_or(Error::ProofIncomplete)?;
+        let solution = proof.solution.as_ref().ok_or(Error::ProofIncomplete)?;
+
+        let y1 = group.decode(&signature.y1)?;
+        let y2 = group.decode(&signature.y2)?;
+        let r1 = group.decode(&commitment.r1)?;
+        let r2 = group.decode(&commitment.r2)?;
+        let s = group.decode_scalar(&solution.s)?;
+        let c = derive_challenge(group, binding, (&y1, &y2), (&r1, &r2), context);
+
+        Ok(Self {
+            y1,
+            y2,
+            r1,
+            r2,
+            c,
+            s,
         })
     }
+
+    fn check(&self, group: &G) -> bool {
+        check_solution(
+            group,
+            (&self.y1, &self.y2),
+            (&self.r1, &self.r2),
+            &self.c,
+            &self.s,
+        )
+    }
+}
+
+/// Checks that the signature was made against exactly the given group.
+pub(super) fn check_group<G: PrimeOrderGroup>(
+    group: &G,
+    signature: &Signature,
+) -> Result<(), Error> {
+    if !group.matches(signature.group.as_ref().ok_or(Error::GroupNotSpecified)?) {
+        return Err(Error::GroupMismatch(group.id().to_string()));
+    }
+
+    Ok(())
+}
+
+/// Checks that `r1 = alpha^s * y1^c` and `r2 = beta^s * y2^c`.
+pub(super) fn check_solution<G: PrimeOrderGroup>(
+    group: &G,
+    (y1, y2): (&G::Element, &G::Element),
+    (r1, r2): (&G::Element, &G::Element),
+    c: &BigUint,
+    s: &BigUint,
+) -> bool {
+    let c1 = *r1 == group.double_exp(group.alpha(), s, y1, c);
+    let c2 = *r2 == group.double_exp(group.beta(), s, y2, c);
+
+    c1 && c2
 }
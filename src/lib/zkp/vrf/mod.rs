@@ -0,0 +1,98 @@
+//! A verifiable random function built on DLEQ proofs, in the style of ECVRF
+//! (RFC 9381) but over any of our groups: the input is hashed to an element
+//! `H`, raised to the user's secret as `gamma = H^x`, and proven to share `x`
+//! with their registered `y1 = alpha^x`. The output is a hash of `gamma`, so
+//! it's unpredictable without the secret, yet unique and publicly verifiable.
+
+use crate::{
+    grpc::auth::{DleqProof, Signature, VrfProof},
+    zkp::{dleq::verify_dleq_proof, secret::Secret, verifier::check_group, Error, PrimeOrderGroup},
+};
+use sha2::{Digest, Sha512};
+
+#[cfg(test)]
+mod test;
+
+/// The protocol and version that VRF inputs and outputs are domain-separated
+/// by. It's also the context of the DLEQ proofs.
+pub const VRF_PROTOCOL_VERSION: &str = "zkp-vrf/v1";
+
+/// Hashes labeled, length-prefixed parts, domain-separated by the VRF protocol.
+fn hash_parts(parts: &[&[u8]]) -> Vec<u8> {
+    let mut hasher = Sha512::new();
+
+    for part in [VRF_PROTOCOL_VERSION.as_bytes()].iter().chain(parts) {
+        hasher.update((part.len() as u64).to_be_bytes());
+        hasher.update(part);
+    }
+
+    hasher.finalize().to_vec()
+}
+
+/// Hashes the input to the element `H`, bound to the group and the public key
+/// `y1`, so that different users' outputs on the same input are unrelated.
+fn hash_input<G: PrimeOrderGroup>(group: &G, y1: &G::Element, input: &[u8]) -> G::Element {
+    group.hash_to_element(&hash_parts(&[
+        b"input",
+        group.id().as_bytes(),
+        &group.encode(y1),
+        input,
+    ]))
+}
+
+/// Derives the VRF output from `gamma`.
+fn output<G: PrimeOrderGroup>(group: &G, gamma: &G::Element) -> Vec<u8> {
+    hash_parts(&[b"output", &group.encode(gamma)])
+}
+
+/// Evaluates the VRF on the input with the secret `x`, returning the output and
+/// its proof. `prove_dleq` proves that two public values share `x`, over the
+/// given bases and in the given context.
+pub(super) fn prove<G: PrimeOrderGroup>(
+    group: &G,
+    secret: &Secret,
+    input: &[u8],
+    prove_dleq: impl FnOnce((&G::Element, &G::Element), &[u8]) -> Result<DleqProof, Error>,
+) -> Result<(Vec<u8>, VrfProof), Error> {
+    let y1 = group.exp_secret(group.alpha(), secret.expose());
+    let h = hash_input(group, &y1, input);
+    let gamma = group.exp_secret(&h, secret.expose());
+
+    let proof = prove_dleq((group.alpha(), &h), VRF_PROTOCOL_VERSION.as_bytes())?;
+
+    Ok((
+        output(group, &gamma),
+        VrfProof {
+            gamma: group.encode(&gamma),
+            proof: Some(proof),
+        },
+    ))
+}
+
+/// Verifies a VRF proof for the input against the given signature's `y1`, and
+/// returns the VRF output if it checks out.
+pub fn verify_vrf<G: PrimeOrderGroup>(
+    group: &G,
+    signature: &Signature,
+    input: &[u8],
+    proof: &VrfProof,
+) -> Result<Vec<u8>, Error> {
+    check_group(group, signature)?;
+
+    let dleq = proof.proof.as_ref().ok_or(Error::ProofIncomplete)?;
+    let y1 = group.decode(&signature.y1)?;
+    let gamma = group.decode(&proof.gamma)?;
+    let h = hash_input(group, &y1, input);
+
+    if !verify_dleq_proof(
+        group,
+        (group.alpha(), &h),
+        (&y1, &gamma),
+        dleq,
+        VRF_PROTOCOL_VERSION.as_bytes(),
+    )? {
+        return Err(Error::InvalidVrfProof);
+    }
+
+    Ok(output(group, &gamma))
+}
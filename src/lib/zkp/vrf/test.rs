@@ -0,0 +1,218 @@
+use crate::{
+    grpc::auth::{DleqProof, VrfProof},
+    zkp::{
+        nonce::NonceMode, ristretto::RISTRETTO255_GROUP, secret::Secret, signer::Signer,
+        test::hex_int, vrf::verify_vrf, Error, PrimeOrderGroup, MODP_1024_160_GROUP,
+    },
+};
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+/// A known-answer test: the input, and the expected `gamma`, output, and a
+/// proof `(c, s)` made for them.
+struct Vector {
+    input: &'static str,
+    gamma: &'static str,
+    output: &'static str,
+    c: &'static str,
+    s: &'static str,
+}
+
+const RISTRETTO_SECRET: &str = "0c5f3f7e1d2a4b6c8e9f0a1b2c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f";
+
+const RISTRETTO_VECTORS: [Vector; 3] = [
+    Vector {
+        input: "",
+        gamma: "3c63122a00f500418bd3b4ab7cd86dd4aab336f27cdb4fe2701f2200e4ceef23",
+        output: "7eb8c82a87bdb3a286c79f9af7c271f411360dec364534d6cc0b441fb7cfe1d0\
+                 bd2843a49c79b5f3c5e8346d233c16bcaf1910a92485e943477525e0556ce24a",
+        c: "05fbe379598013e60db1d103d850609dd46f541b3fba56928daefc324f8fb8b4",
+        s: "099a44d8c016496c9fe8e340cc927177d7e21a553753e4ad07d4daabc5a27212",
+    },
+    Vector {
+        input: "sample",
+        gamma: "b6ae36105fecae39afda3c584f02ee2f8bed1c866729bbc0ac4e629e0cf77c1d",
+        output: "853f4395fce5fc72b628df4854a8e4968349c7cb90c61af3ca727c6f653b135d\
+                 69acfb7fb5fff3af2db6b1e057ca9c2f02b3e8410070e93c10720a087fb46710",
+        c: "0e70af5943679642220a5d79ed6db2b33a9e3b788daf88d7b07b8e9149f17786",
+        s: "061c0f198992acdd13f8407df0aada370d2f9c8270e2c6ee385a37c891e3c9f5",
+    },
+    Vector {
+        input: "lottery round 42",
+        gamma: "3e9ca6a0f25f9baf598b87976e59a8b85811f741dda3655e3e2e811c2e8a610b",
+        output: "6b8dd5cd275b219aac1f9c86d12b7aef37cb6776e719cc46a228dab499064804\
+                 848dfc8130bdd37421ad341aa56a3e0a1ba994fab58c35d8486b264fc9bc2c2e",
+        c: "03cf165fcfb137e5eac64d7f8a299e8226b7728f3dc70d2b1639a69a8014f8c3",
+        s: "0bb9066f1ab5caa0c4419545471d3b759858d0774bb92fbebdcac8c58fb3b4db",
+    },
+];
+
+const MODP_SECRET: &str = "7b3c1d9e2f4a6b8c0d1e2f3a4b5c6d7e8f901a2b";
+
+const MODP_VECTORS: [Vector; 3] = [
+    Vector {
+        input: "",
+        gamma: "5353cd7eca9354568ab9ee619b08af3a118f8a1e84ad526f54f56fce4b63e219\
+                5e8a48eac0158c067ed63f99b315a8cd788c5dc3adda80d7f8d6c2983fbef569\
+                d2c8be1728995e8a922b7c50a402b59d8d30bcf1340380e0b43919b89893ed5f\
+                2f067672501e1ffe7545b642d63b984e7e9ccbd0e3aa4b26093c53d79b3d3946",
+        output: "2160d123f4d5b00b7b0000013b78fc58e95078dbdc13d502f6387eb90236cece\
+                 fd3080e6280c2e09917b26d15fe933d5b63cb747c7564a4b5403ebb387870be8",
+        c: "dd80e7937ff81b6314d4715af0addfcc191646af",
+        s: "ed7cd12b4efe95fbed837f42173465a2f8e9744c",
+    },
+    Vector {
+        input: "sample",
+        gamma: "3dd21492dabfe042746cce52776656eb36d129c74f2d102955e27fc15a9e39d2\
+                4a8f46bcafad864d705f54b075443ced3c24e1fe71cbd72f14a41333267d7a28\
+                6e6938c93b83094ecedf8e13235bf7478101fa2dd4f472a64fffea814ed53ef2\
+                bfc032b0ad5bff2964d00c8f75d43dcaf154140b617d313d07dfa7b453f18d0e",
+        output: "4c5a2c3312b7d19718ea556fbcaed0315026ceed0ab224e8fbbb23ae2a20de34\
+                 4ce36a092e669a8619134043b396b420b499dc2fe7a154e04ec5bb5140650a83",
+        c: "77729e09f43c519d52bb82d8a7d8a7bb465a00aa",
+        s: "4d182b2e997f8de11b930c76d34e402d2aaf8ea8",
+    },
+    Vector {
+        input: "lottery round 42",
+        gamma: "4b6c2b98224451f00817a1173123bb110502332f2b9430d7fe45af054f35c8a4\
+                b87e6167de7d2f71080e55781705524502862df81e9e5efaade7387ccb07d0dd\
+                c531b04df430f3bfc5332b6866d6c839719083a55a53e580bdce4dc3cfa187b3\
+                6f7d9ef34843f756c06a94072aae345f727911bffa3ae6f58119870a36676cc0",
+        output: "56ed7b12e3e697050cdf4ab0c5997750b82cd9ebd3d9ff2a3843242d96cf4b5f\
+                 e2d3230e491465a8ac6ebe07c2e45113c6793aef9bfbc2d663b69ba0cf7cdf1b",
+        c: "df397edf18398cfe29eac81542ccecec6d745a18",
+        s: "0eb551f6eb812ba44f3b0d05b1aab676d68d25d6",
+    },
+];
+
+fn test_vectors_for_group<G: PrimeOrderGroup>(
+    group: &G,
+    secret: &str,
+    vectors: &[Vector],
+) -> TestResult<()> {
+    let signer = Signer::from(group);
+    let secret = Secret::new(hex_int(secret));
+    let signature = signer.create_signature(&secret);
+
+    for vector in vectors {
+        let input = vector.input.as_bytes();
+
+        // Evaluating gives the same gamma and output every time, though the
+        // proof's nonce is fresh.
+        let (output, proof) = signer.prove_vrf(&secret, input)?;
+        assert_eq!(hex::encode(&proof.gamma), vector.gamma, "{}", vector.input);
+        assert_eq!(hex::encode(&output), vector.output, "{}", vector.input);
+        assert_eq!(verify_vrf(group, &signature, input, &proof)?, output);
+
+        // The recorded proof still verifies, to the recorded output.
+        let recorded = VrfProof {
+            gamma: hex::decode(vector.gamma)?,
+            proof: Some(DleqProof {
+                c: hex::decode(vector.c)?,
+                s: hex::decode(vector.s)?,
+            }),
+        };
+        assert_eq!(
+            hex::encode(verify_vrf(group, &signature, input, &recorded)?),
+            vector.output
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn ristretto255_vrf_vectors() -> TestResult<()> {
+    test_vectors_for_group(&*RISTRETTO255_GROUP, RISTRETTO_SECRET, &RISTRETTO_VECTORS)
+}
+
+#[test]
+fn mod_p_vrf_vectors() -> TestResult<()> {
+    test_vectors_for_group(&*MODP_1024_160_GROUP, MODP_SECRET, &MODP_VECTORS)
+}
+
+#[test]
+fn hedged_vrf_gives_the_same_output() -> TestResult<()> {
+    let group = &*RISTRETTO255_GROUP;
+    let secret = Secret::new(hex_int(RISTRETTO_SECRET));
+    let signer = Signer::from((group, NonceMode::Hedged));
+    let signature = signer.create_signature(&secret);
+
+    let (output, proof) = signer.prove_vrf(&secret, b"sample")?;
+
+    assert_eq!(hex::encode(&output), RISTRETTO_VECTORS[1].output);
+    assert_eq!(verify_vrf(group, &signature, b"sample", &proof)?, output);
+
+    Ok(())
+}
+
+#[test]
+fn vrf_outputs_differ_between_users() -> TestResult<()> {
+    let group = &*RISTRETTO255_GROUP;
+    let signer = Signer::from(group);
+
+    let (output1, _) = signer.prove_vrf(&signer.create_random_secret(), b"sample")?;
+    let (output2, _) = signer.prove_vrf(&signer.create_random_secret(), b"sample")?;
+
+    assert_ne!(output1, output2);
+
+    Ok(())
+}
+
+#[test]
+fn vrf_proof_for_another_input_or_user_is_rejected() -> TestResult<()> {
+    let group = &*MODP_1024_160_GROUP;
+    let signer = Signer::from(group);
+    let secret = signer.create_random_secret();
+    let signature = signer.create_signature(&secret);
+    let (_, proof) = signer.prove_vrf(&secret, b"round 1")?;
+
+    assert!(matches!(
+        verify_vrf(group, &signature, b"round 2", &proof),
+        Err(Error::InvalidVrfProof)
+    ));
+
+    let other = signer.create_signature(&signer.create_random_secret());
+    assert!(matches!(
+        verify_vrf(group, &other, b"round 1", &proof),
+        Err(Error::InvalidVrfProof)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn vrf_proof_with_another_gamma_is_rejected() -> TestResult<()> {
+    // Claiming another output (with the same DLEQ proof) must fail.
+    let group = &*RISTRETTO255_GROUP;
+    let signer = Signer::from(group);
+    let secret = signer.create_random_secret();
+    let signature = signer.create_signature(&secret);
+    let (_, mut proof) = signer.prove_vrf(&secret, b"sample")?;
+    let (_, other) = signer.prove_vrf(&secret, b"other")?;
+    proof.gamma = other.gamma;
+
+    assert!(matches!(
+        verify_vrf(group, &signature, b"sample", &proof),
+        Err(Error::InvalidVrfProof)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn vrf_proof_without_dleq_proof_is_incomplete() -> TestResult<()> {
+    let group = &*RISTRETTO255_GROUP;
+    let signer = Signer::from(group);
+    let secret = signer.create_random_secret();
+    let signature = signer.create_signature(&secret);
+    let (_, mut proof) = signer.prove_vrf(&secret, b"sample")?;
+    proof.proof = None;
+
+    assert!(matches!(
+        verify_vrf(group, &signature, b"sample", &proof),
+        Err(Error::ProofIncomplete)
+    ));
+
+    Ok(())
+}
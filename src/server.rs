@@ -1,7 +1,15 @@
-use lib::grpc::auth::{AuthServer, AuthService};
-use tracing::info;
+use lib::{
+    grpc::auth::{AuthServer, AuthService},
+    zkp::{
+        kdf::KdfIssuer,
+        registry::{self, GroupRegistry},
+        Group, PrimeOrderGroup, INSECURE_DEMO_GROUP_IDS,
+    },
+};
+use tracing::{info, warn};
 
 mod config;
+mod server_config;
 mod telemetry;
 
 #[tokio::main]
@@ -13,11 +21,76 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .auth_server_address
         .parse::<std::net::SocketAddr>()?;
 
+    // Register the groups that users are allowed to sign up against.
+    let server_config = server_config::ServerConfig::new()?;
+    let mut groups = GroupRegistry::new().with_min_security_bits(server_config.min_security_bits);
+
+    for id in server_config.accepted_groups.iter() {
+        let is_demo = INSECURE_DEMO_GROUP_IDS.contains(&id.as_str());
+        if is_demo && !cfg!(feature = "insecure-demo-groups") {
+            return Err(format!(
+                "Refusing insecure demo group {}; build with --features insecure-demo-groups to accept it",
+                id
+            )
+            .into());
+        }
+
+        let group = registry::preset(id).ok_or_else(|| format!("Unknown preset group {}", id))?;
+        groups.register(group)?;
+
+        if is_demo {
+            warn!(
+                "INSECURE: accepting sign-ups against demo group {}, whose secrets can be recovered \
+                 instantly; never enable insecure-demo-groups in production",
+                id
+            );
+        } else {
+            info!("Accepting sign-ups against preset group {}", id);
+        }
+    }
+
+    for custom in server_config.custom_groups.iter() {
+        let group = Group::try_from(custom.clone())?.with_fixed_base_tables();
+        groups.register(group)?;
+        info!("Accepting sign-ups against custom group {}", custom.id);
+    }
+
+    for path in server_config.group_files.iter() {
+        let group = Group::load(path)?.with_fixed_base_tables();
+        info!(
+            "Accepting sign-ups against group {} from {}",
+            group.id(),
+            path
+        );
+        groups.register(group)?;
+    }
+
+    // Weaker groups stay registered, so that their users get a clear error, but
+    // aren't offered for sign-ups.
+    info!(
+        "Requiring at least {}-bit security from groups",
+        groups.min_security_bits()
+    );
+    for group in groups.groups() {
+        if let Err(error) = groups.check_security(&group) {
+            warn!("{}; it won't be offered to clients", error);
+        }
+    }
+
+    // Issue per-user salts, and require new users to derive their secrets with at
+    // least the configured Argon2id cost.
+    info!("Requiring a KDF cost of at least {:?}", server_config.kdf);
+    let kdf = KdfIssuer::from(server_config.kdf);
+
     info!("Starting the ZKP auth server at {}", address);
 
     // Start the gRPC authentication server.
     tonic::transport::Server::builder()
-        .add_service(AuthServer::new(AuthService::new()))
+        .add_service(AuthServer::new(AuthService::new(
+            &config::SHARED.server_id,
+            groups,
+            kdf,
+        )))
         .serve(address)
         .await?;
 
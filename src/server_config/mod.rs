@@ -0,0 +1,25 @@
+use lib::zkp::{group_file::GroupDefinition, kdf::KdfCost};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct ServerConfig {
+    pub accepted_groups: Vec<String>,
+    #[serde(default)]
+    pub custom_groups: Vec<GroupDefinition>,
+    #[serde(default)]
+    pub group_files: Vec<String>,
+    #[serde(default)]
+    pub min_security_bits: u32,
+    #[serde(default)]
+    pub kdf: KdfCost,
+}
+
+impl ServerConfig {
+    pub fn new() -> Result<Self, config::ConfigError> {
+        let conf = config::Config::builder()
+            .add_source(config::File::with_name("config/server.toml"))
+            .build()?;
+
+        conf.try_deserialize()
+    }
+}